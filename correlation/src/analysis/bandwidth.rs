@@ -0,0 +1,194 @@
+use crate::decode::Samples;
+use crate::spectrum::Spectrogram;
+use anyhow::Result;
+use ndarray::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const WINDOW: usize = 4096;
+const HOP: usize = 4096;
+
+/// Typical lowpass cutoff in Hz of lossy encoders at a bitrate in kbps
+///
+/// Tracks with a cutoff above the last entry are considered lossless.
+const CUTOFFS: [(f32, u32); 7] = [
+    (11_000.0, 64),
+    (15_000.0, 96),
+    (16_500.0, 128),
+    (17_500.0, 160),
+    (19_000.0, 192),
+    (19_800.0, 256),
+    (20_500.0, 320),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandwidthOptions {
+    /// Width of the band above the cutoff in Hz that must be quieter
+    pub transition_hz: f32,
+    /// Minimum level drop in dB at the cutoff
+    pub min_drop_db: f32,
+    /// Width of the smoothing of the spectrum in Hz
+    pub smoothing_hz: f32,
+}
+
+impl Default for BandwidthOptions {
+    fn default() -> Self {
+        Self {
+            transition_hz: 1_000.0,
+            min_drop_db: 25.0,
+            smoothing_hz: 200.0,
+        }
+    }
+}
+
+/// Effective bandwidth of a decoded track
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bandwidth {
+    /// Frequency in Hz above which the spectrum drops off
+    ///
+    /// This is the nyquist frequency if no lowpass was found.
+    pub cutoff_hz: f32,
+    /// Estimated bitrate in kbps of the lossy source, or `None` if lossless
+    pub estimated_kbps: Option<u32>,
+}
+
+impl Bandwidth {
+    #[must_use]
+    pub fn new(samples: &Samples, options: &BandwidthOptions) -> Option<Self> {
+        let spectrogram = Spectrogram::new(samples, WINDOW, HOP);
+        Self::from_spectrogram(&spectrogram, options)
+    }
+
+    /// Estimates the cutoff from the long term average spectrum
+    ///
+    /// Lossy encoders apply a steep lowpass depending on the bitrate,
+    /// whereas the spectrum of music rolls off gradually.
+    /// The cutoff is the highest frequency whose level exceeds all levels
+    /// above the transition band by the minimum drop.
+    #[must_use]
+    pub fn from_spectrogram(spectrogram: &Spectrogram, options: &BandwidthOptions) -> Option<Self> {
+        if spectrogram.frames() == 0 {
+            return None;
+        }
+        let power = spectrogram.data.mapv(|v| v * v).mean_axis(Axis(0))?;
+        let level = power.mapv(|v| 10.0 * v.max(1e-20).log10());
+        let bin_hz = spectrogram.bin_frequency(1);
+        let radius = (options.smoothing_hz / bin_hz / 2.0).round() as usize;
+        let smoothed = Array::from_iter((0..level.len()).map(|bin| {
+            let lo = bin.saturating_sub(radius);
+            let hi = (bin + radius + 1).min(level.len());
+            level.slice(s![lo..hi]).mean().unwrap_or(f32::MIN)
+        }));
+
+        let transition = (options.transition_hz / bin_hz).ceil() as usize;
+        let nyquist = spectrogram.bin_frequency(spectrogram.bins() - 1);
+        let mut above = f32::MIN;
+        let mut lowpass = None;
+        for bin in (1..smoothed.len().saturating_sub(transition)).rev() {
+            above = above.max(smoothed[bin + transition]);
+            if smoothed[bin] - above >= options.min_drop_db {
+                lowpass = Some(spectrogram.bin_frequency(bin));
+                break;
+            }
+        }
+
+        // without a lowpass the bandwidth is only limited by the sample rate
+        let estimated_kbps = lowpass.and_then(|cutoff| {
+            CUTOFFS
+                .iter()
+                .find(|(max_cutoff, _)| cutoff < *max_cutoff)
+                .map(|(_, kbps)| *kbps)
+        });
+        Some(Self {
+            cutoff_hz: lowpass.unwrap_or(nyquist),
+            estimated_kbps,
+        })
+    }
+
+    /// Decodes a file and estimates its bandwidth
+    ///
+    /// # Errors
+    /// If the file cannot be decoded.
+    pub fn from_file(path: impl AsRef<Path>, options: &BandwidthOptions) -> Result<Option<Self>> {
+        let samples = Samples::from_file(path)?;
+        Ok(Self::new(&samples, options))
+    }
+
+    /// Quality score in [0, 1] for ranking candidates, where 1 is lossless
+    #[must_use]
+    pub fn score(&self) -> f32 {
+        if self.estimated_kbps.is_none() {
+            return 1.0;
+        }
+        let (lowest, _) = CUTOFFS[0];
+        let (highest, _) = CUTOFFS[CUTOFFS.len() - 1];
+        ((self.cutoff_hz - lowest) / (highest - lowest)).clamp(0.0, 1.0)
+    }
+
+    /// Whether the track was likely encoded at a much lower bitrate than claimed
+    #[must_use]
+    pub fn is_upscaled(&self, claimed_kbps: u32) -> bool {
+        self.estimated_kbps
+            .is_some_and(|kbps| kbps * 3 / 2 <= claimed_kbps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bandwidth, BandwidthOptions};
+    use crate::decode::Samples;
+    use crate::verify::tests::noise;
+    use ndarray::prelude::*;
+    use std::f32::consts::PI;
+
+    /// Applies a windowed sinc lowpass filter
+    fn lowpass(data: &Array1<f32>, cutoff: f32, sample_rate: u32) -> Array1<f32> {
+        let taps = 255;
+        let fc = cutoff / sample_rate as f32;
+        let kernel: Vec<f32> = (0..taps)
+            .map(|i| {
+                let x = i as f32 - (taps / 2) as f32;
+                let sinc = if x == 0.0 {
+                    2.0 * fc
+                } else {
+                    (2.0 * PI * fc * x).sin() / (PI * x)
+                };
+                let t = i as f32 / (taps - 1) as f32;
+                let blackman = 0.42 - 0.5 * (2.0 * PI * t).cos() + 0.08 * (4.0 * PI * t).cos();
+                sinc * blackman
+            })
+            .collect();
+        Array::from_iter((0..data.len()).map(|i| {
+            kernel
+                .iter()
+                .enumerate()
+                .filter_map(|(k, c)| {
+                    (i + k)
+                        .checked_sub(taps / 2)
+                        .and_then(|j| data.get(j))
+                        .map(|v| c * v)
+                })
+                .sum()
+        }))
+    }
+
+    #[test]
+    fn test_bandwidth() {
+        let sample_rate = 44_100;
+        let options = BandwidthOptions::default();
+        let white = noise(10 * sample_rate as usize, 1);
+
+        let lossless = Bandwidth::new(&Samples::new(white.clone(), sample_rate), &options).unwrap();
+        assert_eq!(lossless.estimated_kbps, None);
+        assert!((lossless.score() - 1.0).abs() < f32::EPSILON);
+        assert!(!lossless.is_upscaled(192));
+
+        let muddy = lowpass(&white, 16_000.0, sample_rate);
+        let upscaled = Bandwidth::new(&Samples::new(muddy, sample_rate), &options).unwrap();
+        assert!((15_500.0..16_500.0).contains(&upscaled.cutoff_hz));
+        assert_eq!(upscaled.estimated_kbps, Some(128));
+        assert!(upscaled.score() < lossless.score());
+        assert!(upscaled.is_upscaled(192));
+        assert!(!upscaled.is_upscaled(128));
+    }
+}
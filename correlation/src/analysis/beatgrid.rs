@@ -0,0 +1,286 @@
+use super::onset::OnsetEnvelope;
+use super::tempo::{self, Tempo, TempoOptions};
+use ndarray::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeatGridOptions {
+    pub beats_per_bar: u8,
+    /// Length of the sections that are checked for tempo changes
+    pub section_secs: f32,
+    /// Minimum deviation from the current tempo that is considered a tempo change
+    pub tempo_change_bpm: f32,
+}
+
+impl Default for BeatGridOptions {
+    fn default() -> Self {
+        Self {
+            beats_per_bar: 4,
+            section_secs: 30.0,
+            tempo_change_bpm: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TempoChange {
+    /// Time of the first beat with the new tempo in seconds
+    pub position_secs: f64,
+    pub bpm: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeatGrid {
+    /// Time of the first beat in seconds
+    pub anchor_secs: f64,
+    pub bpm: f32,
+    pub beats_per_bar: u8,
+    /// Index of the first downbeat counted from the anchor, if bars were detected
+    pub downbeat: Option<u8>,
+    pub tempo_changes: Vec<TempoChange>,
+}
+
+impl BeatGrid {
+    /// Times of all beats in seconds up to the duration of the track
+    #[must_use]
+    pub fn beats(&self, duration: Duration) -> Vec<f64> {
+        let end = duration.as_secs_f64();
+        let mut beats = Vec::new();
+        let mut position = self.anchor_secs;
+        let mut bpm = self.bpm;
+        let mut changes = self.tempo_changes.iter().peekable();
+        while position < end {
+            if let Some(change) = changes.next_if(|change| change.position_secs <= position) {
+                position = change.position_secs;
+                bpm = change.bpm;
+            }
+            beats.push(position);
+            position += 60.0 / f64::from(bpm.max(1.0));
+        }
+        beats
+    }
+
+    /// Times of all downbeats in seconds up to the duration of the track
+    #[must_use]
+    pub fn downbeats(&self, duration: Duration) -> Vec<f64> {
+        let Some(downbeat) = self.downbeat else {
+            return Vec::new();
+        };
+        self.beats(duration)
+            .into_iter()
+            .skip(usize::from(downbeat))
+            .step_by(usize::from(self.beats_per_bar.max(1)))
+            .collect()
+    }
+}
+
+/// Onset strength smoothed over the direct neighbours
+///
+/// This tolerates beat positions that are slightly off.
+fn smooth(onsets: &Array1<f32>) -> Array1<f32> {
+    Array::from_iter((0..onsets.len()).map(|i| {
+        let prev = onsets[i.saturating_sub(1)];
+        let next = onsets[(i + 1).min(onsets.len() - 1)];
+        0.25 * prev + 0.5 * onsets[i] + 0.25 * next
+    }))
+}
+
+/// Fractional frames of all beats for a beat period and phase in frames
+fn beat_frames(len: usize, period: f32, phase: f32) -> impl Iterator<Item = f32> {
+    (0..)
+        .map(move |k| phase + k as f32 * period)
+        .take_while(move |frame| *frame < (len - 1) as f32)
+}
+
+/// Onset strength at a fractional frame using linear interpolation
+fn interpolate(onsets: &Array1<f32>, frame: f32) -> f32 {
+    let lo = frame.floor() as usize;
+    let fraction = frame - lo as f32;
+    (1.0 - fraction) * onsets[lo] + fraction * onsets[lo + 1]
+}
+
+/// Mean onset strength at the beats
+fn score(onsets: &Array1<f32>, period: f32, phase: f32) -> f32 {
+    let (sum, count) = beat_frames(onsets.len(), period, phase)
+        .fold((0f32, 0usize), |(sum, count), frame| {
+            (sum + interpolate(onsets, frame), count + 1)
+        });
+    if count > 0 {
+        sum / count as f32
+    } else {
+        0.0
+    }
+}
+
+/// Best phase in frames for a beat period
+fn best_phase(onsets: &Array1<f32>, period: f32) -> (f32, f32) {
+    let steps = (period * 4.0).ceil() as usize;
+    (0..steps)
+        .map(|step| {
+            let phase = step as f32 * 0.25;
+            (phase, score(onsets, period, phase))
+        })
+        .fold(
+            (0.0, f32::MIN),
+            |best, cur| if cur.1 > best.1 { cur } else { best },
+        )
+}
+
+/// Fits the beat period and phase around an estimated tempo
+///
+/// Small errors in the tempo accumulate over the length of a track,
+/// hence the tempo is refined to maximize the onset strength on the grid.
+fn fit(onsets: &Array1<f32>, frame_rate: f32, bpm: f32) -> (f32, f32) {
+    let (bpm, phase, _) = (-50..=50)
+        .map(|step| {
+            let bpm = bpm + step as f32 * 0.02;
+            let period = 60.0 * frame_rate / bpm;
+            let (phase, score) = best_phase(onsets, period);
+            (bpm, phase, score)
+        })
+        .fold((bpm, 0.0, f32::MIN), |best, cur| {
+            if cur.2 > best.2 {
+                cur
+            } else {
+                best
+            }
+        });
+    (bpm, phase)
+}
+
+/// Estimates a beat grid from the onsets of a track with a known tempo
+#[must_use]
+pub fn estimate(
+    onsets: &OnsetEnvelope,
+    tempo: &Tempo,
+    tempo_options: &TempoOptions,
+    options: &BeatGridOptions,
+) -> Option<BeatGrid> {
+    if onsets.len() < 2 || tempo.bpm <= 0.0 {
+        return None;
+    }
+    let smoothed = smooth(&onsets.data);
+    let (bpm, phase) = fit(&smoothed, onsets.frame_rate, tempo.bpm);
+    let period = 60.0 * onsets.frame_rate / bpm;
+
+    // the first beat may be before the first complete analysis frame
+    let beat_secs = 60.0 / f64::from(bpm);
+    let anchor_secs = onsets.time(phase).rem_euclid(beat_secs);
+
+    // downbeats are the most accented beats of a bar
+    let beats_per_bar = usize::from(options.beats_per_bar.max(1));
+    let mut accents = vec![(0f32, 0usize); beats_per_bar];
+    for (k, frame) in beat_frames(smoothed.len(), period, phase).enumerate() {
+        let accent = &mut accents[k % beats_per_bar];
+        *accent = (accent.0 + interpolate(&smoothed, frame), accent.1 + 1);
+    }
+    let accents: Vec<f32> = accents
+        .into_iter()
+        .map(|(sum, count)| if count > 0 { sum / count as f32 } else { 0.0 })
+        .collect();
+    let (strongest, accent) =
+        accents.iter().enumerate().fold(
+            (0, 0f32),
+            |best, (i, v)| if *v > best.1 { (i, *v) } else { best },
+        );
+    let others = accents
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != strongest)
+        .fold(0f32, |acc, (_, v)| acc.max(*v));
+    // skip the beats between the anchor and the phase of the first full frame
+    let skipped = ((onsets.time(phase) - anchor_secs) / beat_secs).round() as usize;
+    let downbeat = (accent > 1.1 * others).then(|| ((strongest + skipped) % beats_per_bar) as u8);
+
+    // look for sections with a different tempo
+    let section = (options.section_secs * onsets.frame_rate) as usize;
+    let mut tempo_changes = Vec::new();
+    let mut current = bpm;
+    if section > 0 {
+        for start in (section..onsets.len()).step_by(section) {
+            let end = (start + section).min(onsets.len());
+            if end - start < section / 2 {
+                break;
+            }
+            let section_onsets = OnsetEnvelope {
+                data: onsets.data.slice(s![start..end]).to_owned(),
+                frame_rate: onsets.frame_rate,
+            };
+            let Some(section_tempo) = tempo::estimate(&section_onsets, tempo_options) else {
+                continue;
+            };
+            if (section_tempo.bpm - current).abs() < options.tempo_change_bpm {
+                continue;
+            }
+            let section_smoothed = smooth(&section_onsets.data);
+            let (section_bpm, section_phase) =
+                fit(&section_smoothed, onsets.frame_rate, section_tempo.bpm);
+            tempo_changes.push(TempoChange {
+                position_secs: onsets.time(start as f32 + section_phase),
+                bpm: section_bpm,
+            });
+            current = section_bpm;
+        }
+    }
+
+    Some(BeatGrid {
+        anchor_secs,
+        bpm,
+        beats_per_bar: options.beats_per_bar,
+        downbeat,
+        tempo_changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate, BeatGridOptions};
+    use crate::analysis::onset::OnsetEnvelope;
+    use crate::analysis::tempo::{self, TempoOptions};
+    use crate::decode::Samples;
+    use approx::assert_abs_diff_eq;
+    use ndarray::prelude::*;
+    use std::time::Duration;
+
+    /// Four to the floor beats where every fourth beat is accented
+    fn bars(bpm: f32, secs: f32, first_beat: f32, first_downbeat: usize) -> Samples {
+        let sample_rate = 22_050;
+        let len = (secs * sample_rate as f32) as usize;
+        let period = 60.0 / bpm * sample_rate as f32;
+        let click = sample_rate / 100;
+        let mut data = Array1::<f32>::zeros(len);
+        for beat in 0.. {
+            let start = (first_beat * sample_rate as f32 + beat as f32 * period) as usize;
+            if start + click >= len {
+                break;
+            }
+            let amplitude = if beat % 4 == first_downbeat { 1.0 } else { 0.4 };
+            for i in 0..click {
+                let decay = 1.0 - i as f32 / click as f32;
+                data[start + i] = amplitude * decay * (i as f32 * 1.3).sin();
+            }
+        }
+        Samples::new(data, sample_rate as u32)
+    }
+
+    #[test]
+    fn test_beat_grid() {
+        let samples = bars(123.0, 60.0, 0.3, 1);
+        let onsets = OnsetEnvelope::new(&samples);
+        let tempo_options = TempoOptions::default();
+        let tempo = tempo::estimate(&onsets, &tempo_options).unwrap();
+        let grid = estimate(&onsets, &tempo, &tempo_options, &BeatGridOptions::default()).unwrap();
+
+        assert_abs_diff_eq!(grid.bpm, 123.0, epsilon = 0.05);
+        assert_abs_diff_eq!(grid.anchor_secs, 0.3, epsilon = 0.02);
+        assert_eq!(grid.downbeat, Some(1));
+        assert!(grid.tempo_changes.is_empty());
+
+        let beats = grid.beats(Duration::from_secs(60));
+        let last = 0.3 + 60.0 / 123.0 * (beats.len() - 1) as f64;
+        assert_abs_diff_eq!(*beats.last().unwrap(), last, epsilon = 0.03);
+        let downbeats = grid.downbeats(Duration::from_secs(60));
+        assert_abs_diff_eq!(downbeats[0], 0.3 + 60.0 / 123.0, epsilon = 0.02);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::decode::Samples;
+use crate::spectrum::Spectrogram;
+use ndarray::prelude::*;
+
+pub const SAMPLE_RATE: u32 = 11_025;
+pub const WINDOW: usize = 4096;
+pub const HOP: usize = 2048;
+
+const MIN_FREQUENCY: f32 = 65.0;
+const MAX_FREQUENCY: f32 = 2_100.0;
+
+/// Energy per pitch class over time
+#[derive(Debug, Clone)]
+pub struct Chromagram {
+    /// Pitch class energies with shape (frames, 12), where 0 is C
+    pub data: Array2<f32>,
+    /// Number of frames per second
+    pub frame_rate: f32,
+}
+
+/// Pitch class (0 is C) of a frequency in Hz
+fn pitch_class(frequency: f32) -> usize {
+    let midi = 69.0 + 12.0 * (frequency / 440.0).log2();
+    (midi.round() as i64).rem_euclid(12) as usize
+}
+
+impl Chromagram {
+    #[must_use]
+    pub fn new(samples: &Samples) -> Self {
+        let samples = samples.resample(SAMPLE_RATE);
+        let spectrogram = Spectrogram::new(&samples, WINDOW, HOP);
+        Self::from_spectrogram(&spectrogram)
+    }
+
+    /// Folds the log magnitudes of all bins within the musical range into pitch classes
+    #[must_use]
+    pub fn from_spectrogram(spectrogram: &Spectrogram) -> Self {
+        let classes: Vec<(usize, usize)> = (1..spectrogram.bins())
+            .filter_map(|bin| {
+                let frequency = spectrogram.bin_frequency(bin);
+                (MIN_FREQUENCY..=MAX_FREQUENCY)
+                    .contains(&frequency)
+                    .then(|| (bin, pitch_class(frequency)))
+            })
+            .collect();
+
+        let mut data = Array2::<f32>::zeros((spectrogram.frames(), 12));
+        for (frame, row) in spectrogram.data.axis_iter(Axis(0)).enumerate() {
+            for (bin, class) in &classes {
+                data[[frame, *class]] += (1.0 + row[*bin]).ln();
+            }
+        }
+        Self {
+            data,
+            frame_rate: 1.0 / spectrogram.hop_secs() as f32,
+        }
+    }
+
+    #[must_use]
+    pub fn frames(&self) -> usize {
+        self.data.nrows()
+    }
+
+    /// Average chroma vector over all frames
+    #[must_use]
+    pub fn mean(&self) -> Array1<f32> {
+        self.data
+            .mean_axis(Axis(0))
+            .unwrap_or_else(|| Array1::zeros(12))
+    }
+}
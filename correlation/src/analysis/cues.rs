@@ -0,0 +1,277 @@
+use super::beatgrid::BeatGrid;
+use crate::decode::Samples;
+use ndarray::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// Cutoff of the lowpass filter that isolates the bass in Hz
+const BASS_CUTOFF: f32 = 150.0;
+const SILENCE_DB: f32 = -80.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueOptions {
+    /// Length of the blocks whose energy is measured
+    pub block_secs: f32,
+    /// Length of the windows before and after a block that are compared
+    pub novelty_secs: f32,
+    /// Minimum length of a segment
+    pub min_segment_secs: f32,
+    /// Minimum change of energy in dB for a segment boundary
+    pub min_novelty_db: f32,
+    /// Segments within this many dB of the loudest segment are considered high energy
+    pub high_energy_db: f32,
+}
+
+impl Default for CueOptions {
+    fn default() -> Self {
+        Self {
+            block_secs: 0.5,
+            novelty_secs: 8.0,
+            min_segment_secs: 8.0,
+            min_novelty_db: 3.0,
+            high_energy_db: 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CueKind {
+    IntroEnd,
+    FirstDrop,
+    Breakdown,
+    OutroStart,
+}
+
+impl CueKind {
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IntroEnd => "Intro End",
+            Self::FirstDrop => "Drop",
+            Self::Breakdown => "Breakdown",
+            Self::OutroStart => "Outro",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CuePoint {
+    pub kind: CueKind,
+    /// Position of the cue in seconds
+    pub position_secs: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    start: usize,
+    /// Mean of the full band and bass energy in dB
+    energy: f32,
+}
+
+/// Energy of the full band and the bass in dB per block
+fn energies(samples: &Samples, block: usize) -> (Array1<f32>, Array1<f32>) {
+    let alpha = 1.0 - (-2.0 * PI * BASS_CUTOFF / samples.sample_rate.max(1) as f32).exp();
+    let mut bass = 0f32;
+    let filtered = samples.data.mapv(|v| {
+        bass += alpha * (v - bass);
+        bass
+    });
+    let db = |data: &Array1<f32>| {
+        Array::from_iter(data.exact_chunks(block).into_iter().map(|chunk| {
+            let ms = chunk.mapv(|v| v * v).mean().unwrap_or(0.0);
+            (10.0 * ms.log10()).max(SILENCE_DB)
+        }))
+    };
+    (db(&samples.data), db(&filtered))
+}
+
+/// Change of the mean energy between the windows before and after each block
+fn novelty(features: &[&Array1<f32>], window: usize) -> Array1<f32> {
+    let len = features.first().map_or(0, |f| f.len());
+    Array::from_iter((0..len).map(|i| {
+        if i < window || i + window > len {
+            return 0.0;
+        }
+        features
+            .iter()
+            .map(|f| {
+                let before = f.slice(s![i - window..i]).mean().unwrap_or(0.0);
+                let after = f.slice(s![i..i + window]).mean().unwrap_or(0.0);
+                (after - before).abs()
+            })
+            .sum::<f32>()
+            / features.len() as f32
+    }))
+}
+
+/// Local maxima of the novelty that are at least the minimum segment length apart
+fn boundaries(novelty: &Array1<f32>, min_segment: usize, threshold: f32) -> Vec<usize> {
+    let mut boundaries: Vec<usize> = Vec::new();
+    for i in 0..novelty.len() {
+        let lo = i.saturating_sub(min_segment);
+        let hi = (i + min_segment + 1).min(novelty.len());
+        let is_peak = novelty[i] >= threshold
+            && novelty
+                .slice(s![lo..hi])
+                .iter()
+                .enumerate()
+                .all(|(k, v)| *v < novelty[i] || (*v == novelty[i] && lo + k >= i));
+        if is_peak && boundaries.last().is_none_or(|last| i - last >= min_segment) {
+            boundaries.push(i);
+        }
+    }
+    boundaries
+}
+
+/// Moves a position to the closest downbeat, or the closest beat if bars are unknown
+fn snap(position: f64, beat_grid: Option<&BeatGrid>, duration: std::time::Duration) -> f64 {
+    let Some(grid) = beat_grid else {
+        return position;
+    };
+    let downbeats = grid.downbeats(duration);
+    let candidates = if downbeats.is_empty() {
+        grid.beats(duration)
+    } else {
+        downbeats
+    };
+    candidates
+        .into_iter()
+        .min_by(|a, b| (a - position).abs().total_cmp(&(b - position).abs()))
+        .unwrap_or(position)
+}
+
+/// Suggests cue points by segmenting the track by energy and novelty
+///
+/// Segment boundaries are the points of largest change in full band and bass energy.
+/// Drops are the starts of high energy segments following a quieter segment,
+/// whereas breakdowns and outros are the starts of quieter segments after a drop.
+#[must_use]
+pub fn suggest(
+    samples: &Samples,
+    beat_grid: Option<&BeatGrid>,
+    options: &CueOptions,
+) -> Vec<CuePoint> {
+    let block = (options.block_secs * samples.sample_rate as f32).max(1.0) as usize;
+    let blocks_per_sec = f64::from(samples.sample_rate) / block as f64;
+    let (full, bass) = energies(samples, block);
+    let window = (options.novelty_secs / options.block_secs).round().max(1.0) as usize;
+    let min_segment = (options.min_segment_secs / options.block_secs)
+        .round()
+        .max(1.0) as usize;
+    let novelty = novelty(&[&full, &bass], window);
+
+    let starts: Vec<usize> = std::iter::once(0)
+        .chain(boundaries(&novelty, min_segment, options.min_novelty_db))
+        .collect();
+    let segments: Vec<Segment> = starts
+        .iter()
+        .enumerate()
+        .map(|(k, start)| {
+            let end = starts.get(k + 1).copied().unwrap_or(full.len());
+            let mean = |f: &Array1<f32>| f.slice(s![*start..end]).mean().unwrap_or(SILENCE_DB);
+            Segment {
+                start: *start,
+                energy: 0.5 * (mean(&full) + mean(&bass)),
+            }
+        })
+        .collect();
+    let Some(loudest) = segments.iter().map(|s| s.energy).reduce(f32::max) else {
+        return Vec::new();
+    };
+    let is_high = |segment: &Segment| segment.energy >= loudest - options.high_energy_db;
+
+    let mut cues = Vec::new();
+    if let Some(intro) = segments.get(1) {
+        cues.push((CueKind::IntroEnd, intro.start));
+    }
+    let first_drop = segments.iter().skip(1).position(is_high).map(|k| k + 1);
+    if let Some(drop) = first_drop {
+        cues.push((CueKind::FirstDrop, segments[drop].start));
+    }
+    let last_high = segments.iter().rposition(is_high);
+    if let (Some(drop), Some(last_high)) = (first_drop, last_high) {
+        if let Some(breakdown) = segments[drop..last_high].iter().find(|s| !is_high(s)) {
+            cues.push((CueKind::Breakdown, breakdown.start));
+        }
+        if let Some(outro) = segments.get(last_high + 1) {
+            cues.push((CueKind::OutroStart, outro.start));
+        }
+    }
+
+    let duration = samples.duration();
+    cues.into_iter()
+        .map(|(kind, start)| CuePoint {
+            kind,
+            position_secs: snap(start as f64 / blocks_per_sec, beat_grid, duration),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{suggest, CueKind, CueOptions};
+    use crate::analysis::beatgrid::BeatGrid;
+    use crate::decode::Samples;
+    use crate::verify::tests::noise;
+    use ndarray::prelude::*;
+
+    const SAMPLE_RATE: u32 = 11_025;
+
+    /// Sections of noise with the given amplitude and optional bass
+    fn track(sections: &[(f32, f32, bool)]) -> Samples {
+        let mut data = Vec::new();
+        for (k, (secs, amplitude, bass)) in sections.iter().enumerate() {
+            let len = (secs * SAMPLE_RATE as f32) as usize;
+            let section = noise(len, k as u64);
+            data.extend(section.iter().enumerate().map(|(i, v)| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let bass = if *bass {
+                    0.6 * (2.0 * std::f32::consts::PI * 55.0 * t).sin()
+                } else {
+                    0.0
+                };
+                amplitude * v + bass
+            }));
+        }
+        Samples::new(Array1::from(data), SAMPLE_RATE)
+    }
+
+    #[test]
+    fn test_suggest_cues() {
+        let samples = track(&[
+            (16.0, 0.05, false),
+            (16.0, 0.15, false),
+            (32.0, 0.3, true),
+            (16.0, 0.1, false),
+            (32.0, 0.3, true),
+            (16.0, 0.05, false),
+        ]);
+        let cues = suggest(&samples, None, &CueOptions::default());
+        let kinds: Vec<CueKind> = cues.iter().map(|cue| cue.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                CueKind::IntroEnd,
+                CueKind::FirstDrop,
+                CueKind::Breakdown,
+                CueKind::OutroStart
+            ]
+        );
+        for (cue, expected) in cues.iter().zip([16.0, 32.0, 64.0, 112.0]) {
+            assert!((cue.position_secs - expected).abs() <= 0.5, "{cue:?}");
+        }
+
+        // cues are moved onto the downbeats of the grid
+        let grid = BeatGrid {
+            anchor_secs: 0.2,
+            bpm: 120.0,
+            beats_per_bar: 4,
+            downbeat: Some(0),
+            tempo_changes: Vec::new(),
+        };
+        let cues = suggest(&samples, Some(&grid), &CueOptions::default());
+        for (cue, expected) in cues.iter().zip([16.2, 32.2, 64.2, 112.2]) {
+            assert!((cue.position_secs - expected).abs() < 1e-6, "{cue:?}");
+        }
+    }
+}
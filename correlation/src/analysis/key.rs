@@ -0,0 +1,201 @@
+use super::chroma::Chromagram;
+use ndarray::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Krumhansl-Kessler probe tone profile of C major
+const MAJOR_PROFILE: [f32; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+
+/// Krumhansl-Kessler probe tone profile of C minor
+const MINOR_PROFILE: [f32; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+const PITCH_CLASSES: [&str; 12] = [
+    "C", "Db", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Mode {
+    Major,
+    Minor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Key {
+    /// Pitch class of the tonic, where 0 is C
+    pub tonic: u8,
+    pub mode: Mode,
+    /// Correlation of the chroma with the key profile in [0, 1]
+    pub confidence: f32,
+}
+
+impl Key {
+    /// Standard notation, e.g. `Ab` for A flat major and `F#m` for F sharp minor
+    #[must_use]
+    pub fn notation(&self) -> String {
+        let tonic = PITCH_CLASSES[usize::from(self.tonic % 12)];
+        match self.mode {
+            Mode::Major => tonic.to_string(),
+            Mode::Minor => format!("{tonic}m"),
+        }
+    }
+
+    /// Position on the circle of fifths, where 1 is C major and A minor
+    fn circle_position(&self) -> u8 {
+        let relative_major = match self.mode {
+            Mode::Major => self.tonic % 12,
+            Mode::Minor => (self.tonic + 3) % 12,
+        };
+        (relative_major * 7) % 12 + 1
+    }
+
+    /// Camelot wheel code, e.g. `8B` for C major and `8A` for A minor
+    #[must_use]
+    pub fn camelot(&self) -> String {
+        let number = (self.circle_position() + 6) % 12 + 1;
+        match self.mode {
+            Mode::Major => format!("{number}B"),
+            Mode::Minor => format!("{number}A"),
+        }
+    }
+
+    /// Open Key notation, e.g. `1d` for C major and `1m` for A minor
+    #[must_use]
+    pub fn open_key(&self) -> String {
+        let number = self.circle_position();
+        match self.mode {
+            Mode::Major => format!("{number}d"),
+            Mode::Minor => format!("{number}m"),
+        }
+    }
+}
+
+impl std::fmt::Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.notation())
+    }
+}
+
+/// Pearson correlation of two equally sized vectors
+fn pearson(a: &ArrayView1<f32>, b: &ArrayView1<f32>) -> f32 {
+    let n = a.len() as f32;
+    let mean_a = a.sum() / n;
+    let mean_b = b.sum() / n;
+    let a = a.mapv(|v| v - mean_a);
+    let b = b.mapv(|v| v - mean_b);
+    let norm = (a.dot(&a) * b.dot(&b)).sqrt();
+    if norm > f32::EPSILON {
+        a.dot(&b) / norm
+    } else {
+        0.0
+    }
+}
+
+/// Estimates the key by correlating the average chroma with all 24 key profiles
+#[must_use]
+pub fn estimate(chroma: &Chromagram) -> Option<Key> {
+    if chroma.frames() == 0 {
+        return None;
+    }
+    let mean = chroma.mean();
+    let mut best: Option<Key> = None;
+    for (mode, profile) in [(Mode::Major, MAJOR_PROFILE), (Mode::Minor, MINOR_PROFILE)] {
+        let profile = Array1::from(profile.to_vec());
+        for tonic in 0..12 {
+            // rotate the chroma such that the candidate tonic becomes C
+            let rotated = Array1::from_iter((0..12).map(|i| mean[(i + tonic) % 12]));
+            let score = pearson(&rotated.view(), &profile.view());
+            if best.is_none_or(|best| score > best.confidence) {
+                best = Some(Key {
+                    tonic: tonic as u8,
+                    mode,
+                    confidence: score,
+                });
+            }
+        }
+    }
+    best.map(|key| Key {
+        confidence: key.confidence.clamp(0.0, 1.0),
+        ..key
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate, Key, Mode};
+    use crate::analysis::chroma::Chromagram;
+    use crate::decode::Samples;
+    use ndarray::prelude::*;
+
+    fn tone(midi: f32, secs: f32, sample_rate: u32) -> Array1<f32> {
+        let frequency = 440.0 * 2f32.powf((midi - 69.0) / 12.0);
+        let len = (secs * sample_rate as f32) as usize;
+        Array::from_iter((0..len).map(|i| {
+            (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate as f32).sin()
+        }))
+    }
+
+    fn chords(chords: &[&[f32]], sample_rate: u32) -> Samples {
+        let mut data = Vec::new();
+        for chord in chords {
+            let mut sum = Array1::<f32>::zeros((sample_rate * 2) as usize);
+            for note in *chord {
+                sum = sum + tone(*note, 2.0, sample_rate);
+            }
+            data.extend(sum.iter());
+        }
+        Samples::new(Array1::from(data), sample_rate)
+    }
+
+    #[test]
+    fn test_key_estimation() {
+        let sample_rate = 11_025;
+        // I IV V I in C major
+        let c_major = chords(
+            &[
+                &[48.0, 60.0, 64.0, 67.0],
+                &[53.0, 60.0, 65.0, 69.0],
+                &[55.0, 59.0, 62.0, 67.0],
+                &[48.0, 60.0, 64.0, 67.0],
+            ],
+            sample_rate,
+        );
+        let key = estimate(&Chromagram::new(&c_major)).unwrap();
+        assert_eq!((key.tonic, key.mode), (0, Mode::Major));
+        assert_eq!(key.camelot(), "8B");
+        assert_eq!(key.open_key(), "1d");
+
+        // i iv V i in A minor
+        let a_minor = chords(
+            &[
+                &[45.0, 57.0, 60.0, 64.0],
+                &[50.0, 57.0, 62.0, 65.0],
+                &[52.0, 56.0, 59.0, 64.0],
+                &[45.0, 57.0, 60.0, 64.0],
+            ],
+            sample_rate,
+        );
+        let key = estimate(&Chromagram::new(&a_minor)).unwrap();
+        assert_eq!((key.tonic, key.mode), (9, Mode::Minor));
+        assert_eq!(key.notation(), "Am");
+        assert_eq!(key.camelot(), "8A");
+        assert_eq!(key.open_key(), "1m");
+    }
+
+    #[test]
+    fn test_key_notation() {
+        let key = |tonic, mode| Key {
+            tonic,
+            mode,
+            confidence: 1.0,
+        };
+        assert_eq!(key(6, Mode::Minor).notation(), "F#m");
+        assert_eq!(key(6, Mode::Minor).camelot(), "11A");
+        assert_eq!(key(6, Mode::Minor).open_key(), "4m");
+        assert_eq!(key(8, Mode::Major).notation(), "Ab");
+        assert_eq!(key(8, Mode::Major).camelot(), "4B");
+        assert_eq!(key(8, Mode::Major).open_key(), "9d");
+    }
+}
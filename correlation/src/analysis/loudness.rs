@@ -0,0 +1,356 @@
+use crate::decode::Samples;
+use anyhow::Result;
+use ndarray::prelude::*;
+use rodio::{source::Source, Decoder};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Reference loudness of ReplayGain 2.0 in LUFS
+pub const REPLAYGAIN_REFERENCE: f64 = -18.0;
+
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+const RANGE_RELATIVE_GATE: f64 = -20.0;
+
+/// Gating blocks are made up of sub blocks of 100ms
+const SUB_BLOCKS_PER_SEC: u32 = 10;
+const MOMENTARY_SUB_BLOCKS: usize = 4;
+const SHORT_TERM_SUB_BLOCKS: usize = 30;
+
+/// True peak is measured after oversampling to at least this rate
+const TRUE_PEAK_RATE: u32 = 192_000;
+const TRUE_PEAK_TAPS: usize = 12;
+
+/// Loudness of a track according to ITU-R BS.1770 and EBU R 128
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Loudness {
+    /// Integrated loudness in LUFS
+    pub integrated: f64,
+    /// Maximum inter sample peak in dBTP
+    pub true_peak: f64,
+    /// Loudness range in LU
+    pub range: f64,
+}
+
+impl Loudness {
+    /// Measures the loudness of planar channels
+    ///
+    /// Returns `None` if the audio is too short or too quiet to be measured.
+    #[must_use]
+    pub fn new(channels: &[Array1<f32>], sample_rate: u32) -> Option<Self> {
+        if channels.is_empty() || sample_rate < SUB_BLOCKS_PER_SEC {
+            return None;
+        }
+        let weights = channel_weights(channels.len());
+        let sub_block = (sample_rate / SUB_BLOCKS_PER_SEC) as usize;
+        let sub_blocks = channels.iter().map(Array1::len).min()? / sub_block;
+
+        // weighted mean square of the k-weighted channels per sub block
+        let mut energies = vec![0f64; sub_blocks];
+        for (channel, weight) in channels.iter().zip(weights) {
+            if weight == 0.0 {
+                continue;
+            }
+            let filtered = k_weighting(channel, sample_rate);
+            for (energy, chunk) in energies.iter_mut().zip(filtered.chunks_exact(sub_block)) {
+                *energy += weight * chunk.iter().map(|v| v * v).sum::<f64>() / sub_block as f64;
+            }
+        }
+
+        let momentary = block_energies(&energies, MOMENTARY_SUB_BLOCKS);
+        let integrated = gated_loudness(&momentary)?;
+        let short_term = block_energies(&energies, SHORT_TERM_SUB_BLOCKS);
+        let true_peak = channels
+            .iter()
+            .map(|channel| true_peak(channel, sample_rate))
+            .fold(0f32, f32::max);
+
+        Some(Self {
+            integrated,
+            true_peak: 20.0 * f64::from(true_peak.max(f32::MIN_POSITIVE)).log10(),
+            range: loudness_range(&short_term),
+        })
+    }
+
+    /// Measures the loudness of mono samples
+    #[must_use]
+    pub fn from_samples(samples: &Samples) -> Option<Self> {
+        Self::new(std::slice::from_ref(&samples.data), samples.sample_rate)
+    }
+
+    /// Decodes a source and measures the loudness of all its channels
+    pub fn from_source<S>(source: S) -> Option<Self>
+    where
+        S: Source<Item = f32>,
+    {
+        let sample_rate = source.sample_rate();
+        let channels = usize::from(source.channels().max(1));
+        let interleaved: Vec<f32> = source.collect();
+        let frames = interleaved.len() / channels;
+        let planar: Vec<Array1<f32>> = (0..channels)
+            .map(|c| Array::from_iter((0..frames).map(|i| interleaved[i * channels + c])))
+            .collect();
+        Self::new(&planar, sample_rate)
+    }
+
+    /// Decodes and measures any file supported by the decoder
+    ///
+    /// # Errors
+    /// If the file cannot be opened or decoded.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        let file = BufReader::new(File::open(path)?);
+        let source = Decoder::new(file)?.convert_samples::<f32>();
+        Ok(Self::from_source(source))
+    }
+
+    /// ReplayGain 2.0 track gain in dB
+    #[must_use]
+    pub fn replaygain_gain(&self) -> f64 {
+        REPLAYGAIN_REFERENCE - self.integrated
+    }
+
+    /// ReplayGain track peak as a linear amplitude, where 1.0 is full scale
+    #[must_use]
+    pub fn replaygain_peak(&self) -> f64 {
+        10f64.powf(self.true_peak / 20.0)
+    }
+}
+
+/// Channel weights of BS.1770, where the LFE channel of 5.1 audio is ignored
+fn channel_weights(channels: usize) -> Vec<f64> {
+    match channels {
+        6 => vec![1.0, 1.0, 1.0, 0.0, 1.41, 1.41],
+        n => vec![1.0; n],
+    }
+}
+
+/// Second order IIR filter in direct form I
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+impl Biquad {
+    fn apply(&self, input: &[f64]) -> Vec<f64> {
+        let (mut x1, mut x2, mut y1, mut y2) = (0f64, 0f64, 0f64, 0f64);
+        input
+            .iter()
+            .map(|&x| {
+                let y = self.b[0] * x + self.b[1] * x1 + self.b[2] * x2
+                    - self.a[0] * y1
+                    - self.a[1] * y2;
+                (x2, x1, y2, y1) = (x1, x, y1, y);
+                y
+            })
+            .collect()
+    }
+}
+
+/// K-weighting filter of BS.1770 for an arbitrary sample rate
+///
+/// The coefficients are derived from the analog prototypes, which yields
+/// the coefficients of the standard at 48 kHz.
+fn k_weighting(channel: &Array1<f32>, sample_rate: u32) -> Vec<f64> {
+    let rate = f64::from(sample_rate);
+
+    // high shelf modelling the acoustic effect of the head
+    let (f0, gain, q) = (
+        1_681.974_450_955_533,
+        3.999_843_853_973_347,
+        0.707_175_236_955_419_6,
+    );
+    let k = (PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain / 20.0);
+    let vb = vh.powf(0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    // revised low frequency B-weighting highpass
+    let (f0, q) = (38.135_470_876_024_44, 0.500_327_037_323_877_3);
+    let k = (PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let highpass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    let input: Vec<f64> = channel.iter().map(|v| f64::from(*v)).collect();
+    highpass.apply(&shelf.apply(&input))
+}
+
+/// Mean energies of overlapping blocks made up of the given number of sub blocks
+fn block_energies(energies: &[f64], len: usize) -> Vec<f64> {
+    energies
+        .windows(len)
+        .map(|window| window.iter().sum::<f64>() / len as f64)
+        .collect()
+}
+
+fn loudness(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+fn energy(loudness: f64) -> f64 {
+    10f64.powf((loudness + 0.691) / 10.0)
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Integrated loudness of the blocks after absolute and relative gating
+fn gated_loudness(blocks: &[f64]) -> Option<f64> {
+    let absolute = energy(ABSOLUTE_GATE);
+    let ungated = mean(blocks.iter().copied().filter(|e| *e > absolute))?;
+    let relative = energy(loudness(ungated) + RELATIVE_GATE);
+    let gated = mean(
+        blocks
+            .iter()
+            .copied()
+            .filter(|e| *e > absolute && *e > relative),
+    )?;
+    Some(loudness(gated))
+}
+
+/// Loudness range of EBU Tech 3342 from the short term loudness
+///
+/// This is the spread between the 10th and 95th percentile of the gated
+/// short term loudness distribution.
+fn loudness_range(blocks: &[f64]) -> f64 {
+    let absolute = energy(ABSOLUTE_GATE);
+    let Some(ungated) = mean(blocks.iter().copied().filter(|e| *e > absolute)) else {
+        return 0.0;
+    };
+    let relative = energy(loudness(ungated) + RANGE_RELATIVE_GATE);
+    let mut gated: Vec<f64> = blocks
+        .iter()
+        .copied()
+        .filter(|e| *e > absolute && *e > relative)
+        .map(loudness)
+        .collect();
+    if gated.is_empty() {
+        return 0.0;
+    }
+    gated.sort_by(f64::total_cmp);
+    let percentile = |p: f64| gated[((gated.len() - 1) as f64 * p).round() as usize];
+    percentile(0.95) - percentile(0.10)
+}
+
+/// Maximum absolute amplitude after oversampling with windowed sinc interpolation
+fn true_peak(channel: &Array1<f32>, sample_rate: u32) -> f32 {
+    let sample_peak = channel.fold(0f32, |acc, v| acc.max(v.abs()));
+    let factor = (TRUE_PEAK_RATE / sample_rate.max(1)).max(1) as usize;
+    if factor == 1 || channel.is_empty() {
+        return sample_peak;
+    }
+
+    // one set of interpolation coefficients per fractional position
+    let half = TRUE_PEAK_TAPS / 2;
+    let phases: Vec<Vec<f32>> = (1..factor)
+        .map(|phase| {
+            let fraction = phase as f64 / factor as f64;
+            (0..TRUE_PEAK_TAPS)
+                .map(|tap| {
+                    let x = tap as f64 - (half - 1) as f64 - fraction;
+                    let sinc = if x.abs() < 1e-9 {
+                        1.0
+                    } else {
+                        (PI * x).sin() / (PI * x)
+                    };
+                    let window = 0.5 + 0.5 * (PI * x / (half as f64 + 1.0)).cos();
+                    (sinc * window) as f32
+                })
+                .collect()
+        })
+        .collect();
+
+    let len = channel.len();
+    let mut peak = sample_peak;
+    for i in 0..len {
+        for coefficients in &phases {
+            let value: f32 = coefficients
+                .iter()
+                .enumerate()
+                .map(|(tap, c)| {
+                    let index = (i + tap).checked_sub(half - 1).filter(|index| *index < len);
+                    index.map_or(0.0, |index| c * channel[index])
+                })
+                .sum();
+            peak = peak.max(value.abs());
+        }
+    }
+    peak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Loudness;
+    use approx::assert_abs_diff_eq;
+    use ndarray::prelude::*;
+
+    fn sine(amplitude: f32, frequency: f32, secs: f32, sample_rate: u32) -> Array1<f32> {
+        let len = (secs * sample_rate as f32) as usize;
+        Array::from_iter((0..len).map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            amplitude * (2.0 * std::f32::consts::PI * frequency * t).sin()
+        }))
+    }
+
+    #[test]
+    fn test_loudness_of_sine() {
+        // a 1 kHz sine at -20 dBFS in both channels measures -20 LUFS
+        let sample_rate = 48_000;
+        let channel = sine(0.1, 1_000.0, 10.0, sample_rate);
+        let loudness = Loudness::new(&[channel.clone(), channel], sample_rate).unwrap();
+        assert_abs_diff_eq!(loudness.integrated, -20.0, epsilon = 0.1);
+        assert_abs_diff_eq!(loudness.true_peak, -20.0, epsilon = 0.1);
+        assert_abs_diff_eq!(loudness.range, 0.0, epsilon = 0.1);
+        assert_abs_diff_eq!(loudness.replaygain_gain(), 2.0, epsilon = 0.1);
+        assert_abs_diff_eq!(loudness.replaygain_peak(), 0.1, epsilon = 0.002);
+    }
+
+    #[test]
+    fn test_loudness_gating_and_range() {
+        let sample_rate = 44_100;
+        // silence is gated and does not lower the integrated loudness
+        let mut data = sine(0.1, 1_000.0, 20.0, sample_rate).to_vec();
+        data.extend(std::iter::repeat_n(0.0, 20 * sample_rate as usize));
+        let loudness = Loudness::new(&[Array1::from(data)], sample_rate).unwrap();
+        assert_abs_diff_eq!(loudness.integrated, -23.0, epsilon = 0.1);
+
+        // two sections 10 dB apart span a loudness range of about 10 LU
+        let mut data = sine(0.1, 1_000.0, 20.0, sample_rate).to_vec();
+        data.extend(sine(0.1 / 10f32.sqrt(), 1_000.0, 20.0, sample_rate).iter());
+        let loudness = Loudness::new(&[Array1::from(data)], sample_rate).unwrap();
+        assert_abs_diff_eq!(loudness.range, 10.0, epsilon = 0.5);
+
+        assert!(Loudness::new(&[Array1::zeros(sample_rate as usize)], sample_rate).is_none());
+    }
+
+    #[test]
+    fn test_true_peak_between_samples() {
+        // a full scale sine at a quarter of the sample rate whose peaks fall between samples
+        let sample_rate = 48_000;
+        let channel = Array::from_iter((0..sample_rate).map(|i| {
+            let v = std::f32::consts::FRAC_1_SQRT_2;
+            if i % 4 < 2 {
+                v
+            } else {
+                -v
+            }
+        }));
+        let loudness = Loudness::new(&[channel], sample_rate).unwrap();
+        assert_abs_diff_eq!(loudness.true_peak, 0.0, epsilon = 0.3);
+    }
+}
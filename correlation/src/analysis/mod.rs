@@ -0,0 +1,73 @@
+pub mod bandwidth;
+pub mod beatgrid;
+pub mod chroma;
+pub mod cues;
+pub mod key;
+pub mod loudness;
+mod model;
+pub mod onset;
+pub mod tempo;
+pub mod waveform;
+
+pub use bandwidth::{Bandwidth, BandwidthOptions};
+pub use beatgrid::{BeatGrid, BeatGridOptions, TempoChange};
+pub use chroma::Chromagram;
+pub use cues::{CueKind, CueOptions, CuePoint};
+pub use key::{Key, Mode};
+pub use loudness::Loudness;
+pub use onset::OnsetEnvelope;
+pub use tempo::{Tempo, TempoOptions};
+pub use waveform::{Waveform, WaveformLevel, WaveformOptions, WaveformPoint};
+
+use crate::decode::Samples;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AnalysisOptions {
+    pub tempo: TempoOptions,
+    pub beat_grid: BeatGridOptions,
+    pub cues: CueOptions,
+}
+
+/// Musical properties of a track
+///
+/// They are stored with the library entry as the `TrackAnalysis` of the model.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackAnalysis {
+    pub tempo: Option<Tempo>,
+    pub key: Option<Key>,
+    pub beat_grid: Option<BeatGrid>,
+    /// Suggested cue points for exporters
+    #[serde(default)]
+    pub cues: Vec<CuePoint>,
+}
+
+impl TrackAnalysis {
+    #[must_use]
+    pub fn new(samples: &Samples, options: &AnalysisOptions) -> Self {
+        let onsets = OnsetEnvelope::new(samples);
+        let chroma = Chromagram::new(samples);
+        let tempo = tempo::estimate(&onsets, &options.tempo);
+        let beat_grid = tempo.as_ref().and_then(|tempo| {
+            beatgrid::estimate(&onsets, tempo, &options.tempo, &options.beat_grid)
+        });
+        let cues = cues::suggest(samples, beat_grid.as_ref(), &options.cues);
+        Self {
+            tempo,
+            key: key::estimate(&chroma),
+            beat_grid,
+            cues,
+        }
+    }
+
+    /// Decodes and analyzes a file
+    ///
+    /// # Errors
+    /// If the file cannot be decoded.
+    pub fn from_file(path: impl AsRef<Path>, options: &AnalysisOptions) -> Result<Self> {
+        let samples = Samples::from_file(path)?;
+        Ok(Self::new(&samples, options))
+    }
+}
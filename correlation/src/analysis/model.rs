@@ -0,0 +1,182 @@
+//! Conversions to the stored analysis of the shared model
+//!
+//! Values that the model cannot represent, e.g. cue points of an unknown kind,
+//! are dropped when reading a stored analysis.
+
+use super::{BeatGrid, CueKind, CuePoint, Key, Mode, Tempo, TempoChange, TrackAnalysis};
+use djtool_model as model;
+
+impl From<&TrackAnalysis> for model::TrackAnalysis {
+    fn from(analysis: &TrackAnalysis) -> Self {
+        Self {
+            tempo: analysis.tempo.map(|tempo| model::Tempo {
+                bpm: tempo.bpm,
+                confidence: tempo.confidence,
+            }),
+            key: analysis.key.map(|key| model::MusicalKey {
+                tonic: u32::from(key.tonic),
+                mode: match key.mode {
+                    Mode::Major => model::KeyMode::Major,
+                    Mode::Minor => model::KeyMode::Minor,
+                } as i32,
+                confidence: key.confidence,
+            }),
+            beat_grid: analysis.beat_grid.as_ref().map(|grid| model::BeatGrid {
+                anchor_secs: grid.anchor_secs,
+                bpm: grid.bpm,
+                beats_per_bar: u32::from(grid.beats_per_bar),
+                downbeat: grid.downbeat.map(u32::from),
+                tempo_changes: grid
+                    .tempo_changes
+                    .iter()
+                    .map(|change| model::TempoChange {
+                        position_secs: change.position_secs,
+                        bpm: change.bpm,
+                    })
+                    .collect(),
+            }),
+            cues: analysis
+                .cues
+                .iter()
+                .map(|cue| model::CuePoint {
+                    kind: match cue.kind {
+                        CueKind::IntroEnd => model::CueKind::IntroEnd,
+                        CueKind::FirstDrop => model::CueKind::FirstDrop,
+                        CueKind::Breakdown => model::CueKind::Breakdown,
+                        CueKind::OutroStart => model::CueKind::OutroStart,
+                    } as i32,
+                    position_secs: cue.position_secs,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<model::TrackAnalysis> for TrackAnalysis {
+    fn from(analysis: model::TrackAnalysis) -> Self {
+        Self {
+            tempo: analysis.tempo.map(|tempo| Tempo {
+                bpm: tempo.bpm,
+                confidence: tempo.confidence,
+            }),
+            key: analysis.key.and_then(|key| {
+                let mode = match model::KeyMode::from_i32(key.mode)? {
+                    model::KeyMode::Major => Mode::Major,
+                    model::KeyMode::Minor => Mode::Minor,
+                    model::KeyMode::Unspecified => return None,
+                };
+                Some(Key {
+                    tonic: u8::try_from(key.tonic % 12).ok()?,
+                    mode,
+                    confidence: key.confidence,
+                })
+            }),
+            beat_grid: analysis.beat_grid.map(|grid| BeatGrid {
+                anchor_secs: grid.anchor_secs,
+                bpm: grid.bpm,
+                beats_per_bar: u8::try_from(grid.beats_per_bar).unwrap_or(u8::MAX),
+                downbeat: grid.downbeat.and_then(|beat| u8::try_from(beat).ok()),
+                tempo_changes: grid
+                    .tempo_changes
+                    .into_iter()
+                    .map(|change| TempoChange {
+                        position_secs: change.position_secs,
+                        bpm: change.bpm,
+                    })
+                    .collect(),
+            }),
+            cues: analysis
+                .cues
+                .into_iter()
+                .filter_map(|cue| {
+                    let kind = match model::CueKind::from_i32(cue.kind)? {
+                        model::CueKind::IntroEnd => CueKind::IntroEnd,
+                        model::CueKind::FirstDrop => CueKind::FirstDrop,
+                        model::CueKind::Breakdown => CueKind::Breakdown,
+                        model::CueKind::OutroStart => CueKind::OutroStart,
+                        model::CueKind::Unspecified => return None,
+                    };
+                    Some(CuePoint {
+                        kind,
+                        position_secs: cue.position_secs,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use djtool_model as model;
+
+    #[test]
+    fn test_model_round_trip() {
+        let analysis = TrackAnalysis {
+            tempo: Some(Tempo {
+                bpm: 124.0,
+                confidence: 0.8,
+            }),
+            key: Some(Key {
+                tonic: 8,
+                mode: Mode::Major,
+                confidence: 0.6,
+            }),
+            beat_grid: Some(BeatGrid {
+                anchor_secs: 0.12,
+                bpm: 124.0,
+                beats_per_bar: 4,
+                downbeat: Some(2),
+                tempo_changes: vec![TempoChange {
+                    position_secs: 96.5,
+                    bpm: 126.0,
+                }],
+            }),
+            cues: vec![
+                CuePoint {
+                    kind: CueKind::IntroEnd,
+                    position_secs: 31.1,
+                },
+                CuePoint {
+                    kind: CueKind::Breakdown,
+                    position_secs: 124.5,
+                },
+            ],
+        };
+        let stored = model::TrackAnalysis::from(&analysis);
+        assert_eq!(stored.cues[1].kind, model::CueKind::Breakdown as i32);
+        assert_eq!(TrackAnalysis::from(stored), analysis);
+    }
+
+    #[test]
+    fn test_unknown_values_are_dropped() {
+        let stored = model::TrackAnalysis {
+            key: Some(model::MusicalKey {
+                tonic: 0,
+                mode: model::KeyMode::Unspecified as i32,
+                confidence: 1.0,
+            }),
+            cues: vec![
+                model::CuePoint {
+                    kind: 42,
+                    position_secs: 1.0,
+                },
+                model::CuePoint {
+                    kind: model::CueKind::FirstDrop as i32,
+                    position_secs: 2.0,
+                },
+            ],
+            ..Default::default()
+        };
+        let analysis = TrackAnalysis::from(stored);
+        assert_eq!(analysis.key, None);
+        assert_eq!(
+            analysis.cues,
+            vec![CuePoint {
+                kind: CueKind::FirstDrop,
+                position_secs: 2.0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::decode::Samples;
+use crate::spectrum::Spectrogram;
+use ndarray::prelude::*;
+use ndarray::Array;
+
+pub const SAMPLE_RATE: u32 = 11_025;
+pub const WINDOW: usize = 1024;
+pub const HOP: usize = 128;
+
+/// Onset strength envelope of a track
+#[derive(Debug, Clone)]
+pub struct OnsetEnvelope {
+    pub data: Array1<f32>,
+    /// Number of envelope values per second
+    pub frame_rate: f32,
+}
+
+impl OnsetEnvelope {
+    /// Computes the spectral flux of the log magnitude spectrogram
+    ///
+    /// The flux is detrended with a moving average and half wave rectified,
+    /// so that only sudden increases in energy remain.
+    #[must_use]
+    pub fn new(samples: &Samples) -> Self {
+        let samples = samples.resample(SAMPLE_RATE);
+        let spectrogram = Spectrogram::new(&samples, WINDOW, HOP);
+        let frame_rate = samples.sample_rate as f32 / HOP as f32;
+        let log = spectrogram.data.mapv(|v| (1.0 + v).ln());
+
+        let mut flux = Array1::<f32>::zeros(log.nrows());
+        for frame in 1..log.nrows() {
+            flux[frame] = log
+                .row(frame)
+                .iter()
+                .zip(log.row(frame - 1).iter())
+                .map(|(cur, prev)| (cur - prev).max(0.0))
+                .sum();
+        }
+
+        // subtract the local mean over half a second
+        let radius = (frame_rate / 4.0) as usize;
+        let mut cumulative = Vec::with_capacity(flux.len() + 1);
+        cumulative.push(0f32);
+        for (i, v) in flux.iter().enumerate() {
+            cumulative.push(cumulative[i] + v);
+        }
+        let data = Array::from_iter(flux.iter().enumerate().map(|(i, v)| {
+            let lo = i.saturating_sub(radius);
+            let hi = (i + radius + 1).min(flux.len());
+            let mean = (cumulative[hi] - cumulative[lo]) / (hi - lo) as f32;
+            (v - mean).max(0.0)
+        }));
+
+        let max = data.fold(0f32, |acc, v| acc.max(*v));
+        let data = if max > 0.0 { data / max } else { data };
+        Self { data, frame_rate }
+    }
+
+    /// Time in seconds of a (fractional) envelope frame
+    ///
+    /// The flux of an onset peaks when it is at three quarters of the
+    /// analysis window, where the slope of the hann window is the steepest.
+    #[must_use]
+    pub fn time(&self, frame: f32) -> f64 {
+        f64::from(frame + (3 * WINDOW / (4 * HOP)) as f32) / f64::from(self.frame_rate)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Autocorrelation for lags up to `max_lag`, normalized by the zero lag
+    #[must_use]
+    pub fn autocorrelation(&self, max_lag: usize) -> Array1<f32> {
+        let mean = self.data.mean().unwrap_or(0.0);
+        let centered = self.data.mapv(|v| v - mean);
+        let n = centered.len();
+        let mut ac = Array::from_iter((0..=max_lag).map(|lag| {
+            if lag >= n {
+                return 0.0;
+            }
+            centered
+                .slice(s![..n - lag])
+                .dot(&centered.slice(s![lag..]))
+        }));
+        let zero = ac[0];
+        if zero > 0.0 {
+            ac /= zero;
+        }
+        ac
+    }
+}
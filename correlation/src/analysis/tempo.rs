@@ -0,0 +1,139 @@
+use super::onset::OnsetEnvelope;
+use ndarray::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoOptions {
+    /// Lowest tempo that is reported in beats per minute
+    pub min_bpm: f32,
+    /// Highest tempo that is reported in beats per minute
+    ///
+    /// Tempos outside of the range are folded into it by halving or doubling.
+    pub max_bpm: f32,
+}
+
+impl Default for TempoOptions {
+    fn default() -> Self {
+        Self {
+            min_bpm: 70.0,
+            max_bpm: 180.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Tempo {
+    pub bpm: f32,
+    /// Normalized autocorrelation of the onsets at the beat period in [0, 1]
+    pub confidence: f32,
+}
+
+impl Tempo {
+    /// Tempo as the integer value of an ID3 `TBPM` frame
+    #[must_use]
+    pub fn tbpm(&self) -> u32 {
+        self.bpm.round() as u32
+    }
+}
+
+/// Score of a beat period as the weighted autocorrelation at its multiples
+///
+/// Summing over multiples favours the true beat period over its half,
+/// which only matches the offbeats.
+fn comb_score(ac: &Array1<f32>, lag: f32) -> f32 {
+    (1..=4)
+        .map(|k| {
+            let l = (lag * k as f32).round() as usize;
+            ac.get(l).copied().unwrap_or(0.0) / k as f32
+        })
+        .sum()
+}
+
+/// Refines an integer lag to sub frame precision by parabolic interpolation
+fn interpolate(ac: &Array1<f32>, lag: usize) -> f32 {
+    if lag == 0 || lag + 1 >= ac.len() {
+        return lag as f32;
+    }
+    let (a, b, c) = (ac[lag - 1], ac[lag], ac[lag + 1]);
+    let denominator = a - 2.0 * b + c;
+    if denominator.abs() < f32::EPSILON {
+        return lag as f32;
+    }
+    lag as f32 + (0.5 * (a - c) / denominator).clamp(-0.5, 0.5)
+}
+
+/// Folds a tempo into the range by halving or doubling it
+fn fold(mut bpm: f32, options: &TempoOptions) -> f32 {
+    while bpm > options.max_bpm && bpm / 2.0 >= options.min_bpm {
+        bpm /= 2.0;
+    }
+    while bpm < options.min_bpm && bpm * 2.0 <= options.max_bpm {
+        bpm *= 2.0;
+    }
+    bpm
+}
+
+/// Estimates the tempo from the autocorrelation of the onset strength
+#[must_use]
+pub fn estimate(onsets: &OnsetEnvelope, options: &TempoOptions) -> Option<Tempo> {
+    if onsets.is_empty() || options.min_bpm <= 0.0 || options.max_bpm < options.min_bpm {
+        return None;
+    }
+    let to_lag = |bpm: f32| 60.0 * onsets.frame_rate / bpm;
+    let min_lag = to_lag(options.max_bpm).floor().max(1.0) as usize;
+    let max_lag = to_lag(options.min_bpm).ceil() as usize;
+    let ac = onsets.autocorrelation(4 * (max_lag + 1));
+
+    let (lag, _) = (min_lag..=max_lag)
+        .map(|lag| (lag, comb_score(&ac, lag as f32)))
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+
+    let period = interpolate(&ac, lag);
+    let bpm = fold(60.0 * onsets.frame_rate / period, options);
+    Some(Tempo {
+        bpm,
+        confidence: ac[lag].clamp(0.0, 1.0),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{estimate, TempoOptions};
+    use crate::analysis::onset::OnsetEnvelope;
+    use crate::decode::Samples;
+    use approx::assert_abs_diff_eq;
+    use ndarray::prelude::*;
+
+    /// Click track with accented beats and quieter offbeats
+    pub(crate) fn clicks(bpm: f32, secs: f32, sample_rate: u32, offset: f32) -> Samples {
+        let len = (secs * sample_rate as f32) as usize;
+        let period = 60.0 / bpm * sample_rate as f32;
+        let click = sample_rate as usize / 100;
+        let mut data = Array1::<f32>::zeros(len);
+        let mut beat = 0;
+        loop {
+            let start = (offset * sample_rate as f32 + beat as f32 * period / 2.0) as usize;
+            if start + click >= len {
+                break;
+            }
+            let amplitude = if beat % 2 == 0 { 1.0 } else { 0.3 };
+            for i in 0..click {
+                let decay = 1.0 - i as f32 / click as f32;
+                data[start + i] = amplitude * decay * (i as f32 * 1.3).sin();
+            }
+            beat += 1;
+        }
+        Samples::new(data, sample_rate)
+    }
+
+    #[test]
+    fn test_tempo() {
+        let options = TempoOptions::default();
+        for bpm in [90.0, 128.0, 174.0] {
+            let samples = clicks(bpm, 30.0, 22_050, 0.0);
+            let tempo = estimate(&OnsetEnvelope::new(&samples), &options).unwrap();
+            assert_abs_diff_eq!(tempo.bpm, bpm, epsilon = 1.0);
+            assert!(tempo.confidence > 0.3);
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use crate::decode::Samples;
+use anyhow::{anyhow, Result};
+use std::f32::consts::PI;
+
+const MAGIC: &[u8; 4] = b"DJWF";
+const VERSION: u8 = 1;
+const POINT_BYTES: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaveformOptions {
+    /// Resolution of the most detailed level
+    pub points_per_second: u32,
+    /// Number of levels, each `factor` times coarser than the previous one
+    ///
+    /// At most 255 levels are computed, as the encoding stores the count in a byte.
+    pub levels: usize,
+    pub factor: usize,
+    /// Upper edge of the low band in Hz
+    pub low_cutoff: u32,
+    /// Lower edge of the high band in Hz
+    pub high_cutoff: u32,
+}
+
+impl Default for WaveformOptions {
+    fn default() -> Self {
+        Self {
+            points_per_second: 150,
+            levels: 4,
+            factor: 4,
+            low_cutoff: 250,
+            high_cutoff: 4_000,
+        }
+    }
+}
+
+/// Summary of a block of samples, quantized to a byte per value
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WaveformPoint {
+    pub peak: u8,
+    pub rms: u8,
+    /// RMS of the low, mid and high frequency bands, e.g. for coloring
+    pub low: u8,
+    pub mid: u8,
+    pub high: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaveformLevel {
+    pub samples_per_point: u32,
+    pub points: Vec<WaveformPoint>,
+}
+
+/// Multi resolution waveform overview of a track
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Waveform {
+    pub sample_rate: u32,
+    /// Levels ordered from the most detailed to the coarsest
+    pub levels: Vec<WaveformLevel>,
+}
+
+fn quantize(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn dequantize(value: u8) -> f32 {
+    f32::from(value) / 255.0
+}
+
+/// Smoothing coefficient of a one pole lowpass filter
+fn one_pole(cutoff: u32, sample_rate: u32) -> f32 {
+    1.0 - (-2.0 * PI * cutoff as f32 / sample_rate as f32).exp()
+}
+
+/// Raw (unquantized) statistics of a block of samples
+#[derive(Debug, Default, Clone, Copy)]
+struct Block {
+    peak: f32,
+    squares: f32,
+    low: f32,
+    mid: f32,
+    high: f32,
+    count: usize,
+}
+
+impl Block {
+    fn merge(self, other: Self) -> Self {
+        Self {
+            peak: self.peak.max(other.peak),
+            squares: self.squares + other.squares,
+            low: self.low + other.low,
+            mid: self.mid + other.mid,
+            high: self.high + other.high,
+            count: self.count + other.count,
+        }
+    }
+
+    fn point(&self) -> WaveformPoint {
+        let n = self.count.max(1) as f32;
+        WaveformPoint {
+            peak: quantize(self.peak),
+            rms: quantize((self.squares / n).sqrt()),
+            low: quantize((self.low / n).sqrt()),
+            mid: quantize((self.mid / n).sqrt()),
+            high: quantize((self.high / n).sqrt()),
+        }
+    }
+}
+
+impl Waveform {
+    #[must_use]
+    pub fn new(samples: &Samples, options: &WaveformOptions) -> Self {
+        let sample_rate = samples.sample_rate;
+        let block = (sample_rate / options.points_per_second.max(1)).max(1) as usize;
+        let low_alpha = one_pole(options.low_cutoff, sample_rate);
+        let high_alpha = one_pole(options.high_cutoff, sample_rate);
+
+        // split into bands using one pole filters
+        let (mut low, mut below_high) = (0f32, 0f32);
+        let mut blocks = Vec::with_capacity(samples.len() / block + 1);
+        let mut current = Block::default();
+        for v in &samples.data {
+            low += low_alpha * (v - low);
+            below_high += high_alpha * (v - below_high);
+            let high = v - below_high;
+            let mid = below_high - low;
+            current.peak = current.peak.max(v.abs());
+            current.squares += v * v;
+            current.low += low * low;
+            current.mid += mid * mid;
+            current.high += high * high;
+            current.count += 1;
+            if current.count == block {
+                blocks.push(current);
+                current = Block::default();
+            }
+        }
+        if current.count > 0 {
+            blocks.push(current);
+        }
+
+        let mut levels = Vec::with_capacity(options.levels);
+        let mut samples_per_point = block;
+        for level in 0..options.levels.clamp(1, usize::from(u8::MAX)) {
+            if level > 0 {
+                blocks = blocks
+                    .chunks(options.factor.max(2))
+                    .map(|chunk| chunk.iter().copied().fold(Block::default(), Block::merge))
+                    .collect();
+                samples_per_point = samples_per_point.saturating_mul(options.factor.max(2));
+            }
+            levels.push(WaveformLevel {
+                samples_per_point: u32::try_from(samples_per_point).unwrap_or(u32::MAX),
+                points: blocks.iter().map(Block::point).collect(),
+            });
+        }
+        Self {
+            sample_rate,
+            levels,
+        }
+    }
+
+    /// Level with at most the given number of points, e.g. for the width of a view
+    #[must_use]
+    pub fn level_for(&self, max_points: usize) -> Option<&WaveformLevel> {
+        self.levels
+            .iter()
+            .find(|level| level.points.len() <= max_points)
+            .or_else(|| self.levels.last())
+    }
+
+    /// Encodes the waveform into a compact binary blob
+    ///
+    /// Only the 255 most detailed levels are encoded.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let count = u8::try_from(self.levels.len()).unwrap_or(u8::MAX);
+        let levels = &self.levels[..usize::from(count)];
+        let points: usize = levels.iter().map(|level| level.points.len()).sum();
+        let mut bytes = Vec::with_capacity(10 + 8 * levels.len() + POINT_BYTES * points);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.push(count);
+        for level in levels {
+            bytes.extend_from_slice(&level.samples_per_point.to_le_bytes());
+            bytes.extend_from_slice(&(level.points.len() as u32).to_le_bytes());
+            for point in &level.points {
+                bytes.extend_from_slice(&[point.peak, point.rms, point.low, point.mid, point.high]);
+            }
+        }
+        bytes
+    }
+
+    /// Decodes a waveform from a binary blob
+    ///
+    /// # Errors
+    /// If the blob is truncated or not a waveform of a supported version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+            if bytes.len() < len {
+                return Err(anyhow!("truncated waveform"));
+            }
+            let (head, tail) = bytes.split_at(len);
+            *bytes = tail;
+            Ok(head)
+        }
+        fn take_u32(bytes: &mut &[u8]) -> Result<u32> {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(take(bytes, 4)?);
+            Ok(u32::from_le_bytes(buf))
+        }
+
+        let mut bytes = bytes;
+        if take(&mut bytes, MAGIC.len())? != MAGIC {
+            return Err(anyhow!("not a waveform"));
+        }
+        let version = take(&mut bytes, 1)?[0];
+        if version != VERSION {
+            return Err(anyhow!("unsupported waveform version {version}"));
+        }
+        let sample_rate = take_u32(&mut bytes)?;
+        let count = take(&mut bytes, 1)?[0];
+        let mut levels = Vec::with_capacity(usize::from(count));
+        for _ in 0..count {
+            let samples_per_point = take_u32(&mut bytes)?;
+            let len = take_u32(&mut bytes)? as usize;
+            let points = take(&mut bytes, len * POINT_BYTES)?
+                .chunks_exact(POINT_BYTES)
+                .map(|point| WaveformPoint {
+                    peak: point[0],
+                    rms: point[1],
+                    low: point[2],
+                    mid: point[3],
+                    high: point[4],
+                })
+                .collect();
+            levels.push(WaveformLevel {
+                samples_per_point,
+                points,
+            });
+        }
+        Ok(Self {
+            sample_rate,
+            levels,
+        })
+    }
+}
+
+impl WaveformPoint {
+    /// Peak amplitude in [0, 1]
+    #[must_use]
+    pub fn peak(&self) -> f32 {
+        dequantize(self.peak)
+    }
+
+    /// RMS amplitude in [0, 1]
+    #[must_use]
+    pub fn rms(&self) -> f32 {
+        dequantize(self.rms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Waveform, WaveformOptions};
+    use crate::decode::Samples;
+    use ndarray::prelude::*;
+
+    fn tone(frequency: f32, secs: f32, sample_rate: u32) -> Array1<f32> {
+        let len = (secs * sample_rate as f32) as usize;
+        Array::from_iter((0..len).map(|i| {
+            0.5 * (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate as f32).sin()
+        }))
+    }
+
+    #[test]
+    fn test_waveform() {
+        let sample_rate = 22_050;
+        let mut data = tone(150.0, 2.0, sample_rate).to_vec();
+        data.extend(tone(8_000.0, 2.0, sample_rate).iter());
+        let samples = Samples::new(Array1::from(data), sample_rate);
+        let options = WaveformOptions::default();
+        let waveform = Waveform::new(&samples, &options);
+
+        assert_eq!(waveform.levels.len(), options.levels);
+        let finest = &waveform.levels[0];
+        assert_eq!(finest.points.len(), 4 * 150);
+        assert_eq!(
+            waveform.levels[1].points.len(),
+            finest.points.len().div_ceil(4)
+        );
+
+        let bass = finest.points[100];
+        let treble = finest.points[500];
+        assert!((bass.peak() - 0.5).abs() < 0.01);
+        assert!((bass.rms() - 0.5 / 2f32.sqrt()).abs() < 0.01);
+        assert!(bass.low > bass.high);
+        assert!(treble.high > treble.low);
+
+        let decoded = Waveform::from_bytes(&waveform.to_bytes()).unwrap();
+        assert_eq!(decoded, waveform);
+        assert!(Waveform::from_bytes(&waveform.to_bytes()[..20]).is_err());
+    }
+
+    #[test]
+    fn test_waveform_level_count_fits_a_byte() {
+        let samples = Samples::new(tone(440.0, 0.5, 22_050), 22_050);
+        let options = WaveformOptions {
+            levels: 300,
+            ..WaveformOptions::default()
+        };
+        let waveform = Waveform::new(&samples, &options);
+        assert_eq!(waveform.levels.len(), 255);
+
+        let mut levels = waveform.levels.clone();
+        levels.push(levels[254].clone());
+        let oversized = Waveform {
+            levels,
+            ..waveform.clone()
+        };
+        let decoded = Waveform::from_bytes(&oversized.to_bytes()).unwrap();
+        assert_eq!(decoded, waveform);
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::Result;
+use ndarray::prelude::*;
+use ndarray::Array;
+use rayon::prelude::*;
+use rodio::{source::Source, Decoder};
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::Duration;
+
+/// Zero crossings of the windowed sinc on either side of the resampling kernel
+const RESAMPLE_ZEROS: f64 = 16.0;
+
+/// Mono PCM samples of a decoded audio track
+#[derive(Debug, Clone)]
+pub struct Samples {
+    pub data: Array1<f32>,
+    pub sample_rate: u32,
+}
+
+impl Samples {
+    #[must_use]
+    pub fn new(data: Array1<f32>, sample_rate: u32) -> Self {
+        Self { data, sample_rate }
+    }
+
+    /// Decodes a source and mixes all channels down to mono
+    pub fn from_source<S>(source: S) -> Self
+    where
+        S: Source<Item = f32>,
+    {
+        let sample_rate = source.sample_rate();
+        let channels = usize::from(source.channels().max(1));
+        let interleaved: Vec<f32> = source.collect();
+        let data = Array::from_iter(
+            interleaved
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+        Self { data, sample_rate }
+    }
+
+    /// Decodes any file supported by the decoder into mono samples
+    ///
+    /// # Errors
+    /// If the file cannot be opened or decoded.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        let source = Decoder::new(file)?.convert_samples::<f32>();
+        Ok(Self::from_source(source))
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.data.len() as f64 / f64::from(self.sample_rate.max(1)))
+    }
+
+    /// Resamples to the given rate using a windowed sinc interpolator
+    ///
+    /// The kernel is low-passed at the lower of both nyquist frequencies,
+    /// such that downsampling does not alias.
+    #[must_use]
+    pub fn resample(&self, sample_rate: u32) -> Self {
+        let sample_rate = sample_rate.max(1);
+        if sample_rate == self.sample_rate {
+            return self.clone();
+        }
+        let ratio = f64::from(sample_rate) / f64::from(self.sample_rate.max(1));
+        let cutoff = ratio.min(1.0);
+        let half_width = RESAMPLE_ZEROS / cutoff;
+        let len = (self.len() as f64 * ratio).round() as usize;
+        let last = self.len() as isize - 1;
+
+        let data: Vec<f32> = (0..len)
+            .into_par_iter()
+            .map(|i| {
+                let center = i as f64 / ratio;
+                let first = ((center - half_width).ceil() as isize).max(0);
+                let end = ((center + half_width).floor() as isize).min(last);
+                (first..=end)
+                    .map(|j| {
+                        let t = j as f64 - center;
+                        let window = 0.5 + 0.5 * (PI * t / half_width).cos();
+                        let x = PI * cutoff * t;
+                        let sinc = if x.abs() < 1e-9 { 1.0 } else { x.sin() / x };
+                        f64::from(self.data[j as usize]) * cutoff * sinc * window
+                    })
+                    .sum::<f64>() as f32
+            })
+            .collect();
+        Self::new(Array::from_vec(data), sample_rate)
+    }
+
+    /// Number of samples per value of the envelope at the given rate
+    ///
+    /// The actual envelope rate is `sample_rate / block`, which only
+    /// equals the requested rate if it divides the sample rate.
+    #[must_use]
+    pub fn envelope_block(&self, rate: u32) -> usize {
+        (self.sample_rate / rate.max(1)).max(1) as usize
+    }
+
+    /// Computes the amplitude envelope at approximately the given rate
+    ///
+    /// Each envelope value is the peak absolute amplitude of the
+    /// corresponding block of samples.
+    #[must_use]
+    pub fn envelope(&self, rate: u32) -> Array1<f32> {
+        let block = self.envelope_block(rate);
+        Array::from_iter(
+            self.data
+                .exact_chunks(block)
+                .into_iter()
+                .map(|chunk| chunk.fold(0f32, |acc, v| acc.max(v.abs()))),
+        )
+    }
+}
@@ -0,0 +1,284 @@
+use crate::analysis::chroma::Chromagram;
+use crate::decode::Samples;
+use anyhow::Result;
+use ndarray::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DtwOptions {
+    /// Rate of the chroma frames that are aligned
+    ///
+    /// Lower rates are faster and more robust against small timing differences.
+    pub frame_rate: f32,
+    /// Minimum similarity for a candidate to be considered the same song
+    pub min_similarity: f32,
+}
+
+impl Default for DtwOptions {
+    fn default() -> Self {
+        Self {
+            frame_rate: 2.0,
+            min_similarity: 0.5,
+        }
+    }
+}
+
+/// Aligned regions of two versions of a song
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Alignment {
+    pub reference_start: Duration,
+    pub reference_end: Duration,
+    pub candidate_start: Duration,
+    pub candidate_end: Duration,
+    /// Mean correlation of the aligned chroma frames in [0, 1]
+    pub similarity: f32,
+}
+
+impl Alignment {
+    #[must_use]
+    pub fn is_match(&self, options: &DtwOptions) -> bool {
+        self.similarity >= options.min_similarity
+    }
+}
+
+/// Averages chroma frames down to the target rate and normalizes each frame
+///
+/// The frames are centered and scaled to unit length, such that
+/// their dot product is the correlation of the pitch class profiles.
+fn features(chroma: &Chromagram, frame_rate: f32) -> (Array2<f32>, f32) {
+    let factor = (chroma.frame_rate / frame_rate.max(f32::EPSILON))
+        .round()
+        .max(1.0) as usize;
+    let frames = chroma.frames() / factor;
+    let mut data = Array2::<f32>::zeros((frames, 12));
+    for (frame, mut row) in data.axis_iter_mut(Axis(0)).enumerate() {
+        let block = chroma
+            .data
+            .slice(s![frame * factor..(frame + 1) * factor, ..]);
+        let mean = block
+            .mean_axis(Axis(0))
+            .unwrap_or_else(|| Array1::zeros(12));
+        let centered = mean.mapv(|v| v - mean.mean().unwrap_or(0.0));
+        let norm = centered.dot(&centered).sqrt();
+        if norm > f32::EPSILON {
+            row.assign(&(centered / norm));
+        }
+    }
+    (data, chroma.frame_rate / factor as f32)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Start,
+    Diagonal,
+    Query,
+    Database,
+}
+
+/// Subsequence dynamic time warping of a query within a database sequence
+///
+/// The query must be aligned completely, but may start and end anywhere
+/// within the database. Returns the start and end frame in the database,
+/// and the mean cost along the warping path.
+fn subsequence_dtw(cost: &Array2<f32>) -> Option<(usize, usize, f32)> {
+    let (n, m) = cost.dim();
+    if n == 0 || m == 0 {
+        return None;
+    }
+    let mut total = Array2::<f32>::zeros((n, m));
+    let mut length = Array2::<u32>::zeros((n, m));
+    let mut steps = Array2::from_elem((n, m), Step::Start);
+    for i in 0..n {
+        for j in 0..m {
+            let (prev, step) = if i == 0 {
+                // the query may start anywhere in the database
+                ((0.0, 0), Step::Start)
+            } else {
+                let mut best = ((total[[i - 1, j]], length[[i - 1, j]]), Step::Query);
+                let mut consider = |candidate: (f32, u32), step| {
+                    let normalized = |(cost, len): (f32, u32)| cost / len.max(1) as f32;
+                    if normalized(candidate) < normalized(best.0) {
+                        best = (candidate, step);
+                    }
+                };
+                if j > 0 {
+                    consider(
+                        (total[[i - 1, j - 1]], length[[i - 1, j - 1]]),
+                        Step::Diagonal,
+                    );
+                    consider((total[[i, j - 1]], length[[i, j - 1]]), Step::Database);
+                }
+                best
+            };
+            total[[i, j]] = prev.0 + cost[[i, j]];
+            length[[i, j]] = prev.1 + 1;
+            steps[[i, j]] = step;
+        }
+    }
+
+    let normalized = |j: usize| total[[n - 1, j]] / length[[n - 1, j]] as f32;
+    let end = (0..m).min_by(|a, b| normalized(*a).total_cmp(&normalized(*b)))?;
+
+    let (mut i, mut j) = (n - 1, end);
+    loop {
+        match steps[[i, j]] {
+            Step::Start => break,
+            Step::Diagonal => (i, j) = (i - 1, j - 1),
+            Step::Query => i -= 1,
+            Step::Database => j -= 1,
+        }
+    }
+    Some((j, end, normalized(end)))
+}
+
+/// Aligns two chromagrams, where the shorter one is aligned within the longer one
+#[must_use]
+pub fn align_chroma(
+    reference: &Chromagram,
+    candidate: &Chromagram,
+    options: &DtwOptions,
+) -> Option<Alignment> {
+    let (reference, frame_rate) = features(reference, options.frame_rate);
+    let (candidate, _) = features(candidate, options.frame_rate);
+    let swapped = reference.nrows() > candidate.nrows();
+    let (query, database) = if swapped {
+        (&candidate, &reference)
+    } else {
+        (&reference, &candidate)
+    };
+
+    // correlations are in [-1, 1] and are mapped to costs in [0, 1]
+    let cost = query.dot(&database.t()).mapv(|r| (1.0 - r) / 2.0);
+    let (start, end, mean_cost) = subsequence_dtw(&cost)?;
+
+    let time = |frame: usize| Duration::from_secs_f64(frame as f64 / f64::from(frame_rate));
+    let (full, region) = ((time(0), time(query.nrows())), (time(start), time(end + 1)));
+    let ((reference_start, reference_end), (candidate_start, candidate_end)) = if swapped {
+        (region, full)
+    } else {
+        (full, region)
+    };
+    Some(Alignment {
+        reference_start,
+        reference_end,
+        candidate_start,
+        candidate_end,
+        similarity: (1.0 - 2.0 * mean_cost).clamp(0.0, 1.0),
+    })
+}
+
+/// Matches two versions of a song, e.g. a radio edit and an extended mix
+///
+/// Unlike cross correlation of the envelopes, aligning chroma sequences
+/// with dynamic time warping tolerates differences in arrangement and tempo.
+#[must_use]
+pub fn align(reference: &Samples, candidate: &Samples, options: &DtwOptions) -> Option<Alignment> {
+    align_chroma(
+        &Chromagram::new(reference),
+        &Chromagram::new(candidate),
+        options,
+    )
+}
+
+/// Decodes and matches two versions of a song
+///
+/// # Errors
+/// If either of the files cannot be decoded.
+pub fn align_files(
+    reference: impl AsRef<Path>,
+    candidate: impl AsRef<Path>,
+    options: &DtwOptions,
+) -> Result<Option<Alignment>> {
+    let reference = Samples::from_file(reference)?;
+    let candidate = Samples::from_file(candidate)?;
+    Ok(align(&reference, &candidate, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{align, DtwOptions};
+    use crate::decode::Samples;
+    use crate::verify::tests::noise;
+    use ndarray::prelude::*;
+
+    const SAMPLE_RATE: u32 = 11_025;
+
+    /// Chords given as midi notes, each held for the given number of seconds
+    fn song(chords: &[(&[f32], f32)]) -> Samples {
+        let mut data = Vec::new();
+        for (notes, secs) in chords {
+            let len = (secs * SAMPLE_RATE as f32) as usize;
+            data.extend((0..len).map(|i| {
+                notes
+                    .iter()
+                    .map(|midi| {
+                        let frequency = 440.0 * 2f32.powf((midi - 69.0) / 12.0);
+                        let t = i as f32 / SAMPLE_RATE as f32;
+                        (2.0 * std::f32::consts::PI * frequency * t).sin()
+                    })
+                    .sum::<f32>()
+                    / notes.len().max(1) as f32
+            }));
+        }
+        Samples::new(Array1::from(data), SAMPLE_RATE)
+    }
+
+    #[test]
+    fn test_align_versions() {
+        let c: &[f32] = &[48.0, 60.0, 64.0, 67.0];
+        let a_minor: &[f32] = &[45.0, 57.0, 60.0, 64.0];
+        let f: &[f32] = &[53.0, 60.0, 65.0, 69.0];
+        let g: &[f32] = &[55.0, 59.0, 62.0, 67.0];
+        let e: &[f32] = &[52.0, 56.0, 59.0, 64.0];
+        let d_minor: &[f32] = &[50.0, 57.0, 62.0, 65.0];
+        let options = DtwOptions::default();
+
+        let radio_edit = song(&[
+            (c, 4.0),
+            (a_minor, 4.0),
+            (f, 4.0),
+            (g, 4.0),
+            (c, 4.0),
+            (e, 4.0),
+        ]);
+
+        // slower extended mix with a pitchless intro, a repeated section and an outro
+        let mut extended = noise(10 * SAMPLE_RATE as usize, 3)
+            .mapv(|v| 0.3 * v)
+            .to_vec();
+        extended.extend(
+            song(&[
+                (c, 4.4),
+                (a_minor, 4.4),
+                (f, 4.4),
+                (g, 4.4),
+                (f, 4.4),
+                (g, 4.4),
+                (c, 4.4),
+                (e, 4.4),
+            ])
+            .data
+            .iter(),
+        );
+        extended.extend(noise(10 * SAMPLE_RATE as usize, 4).mapv(|v| 0.3 * v).iter());
+        let extended = Samples::new(Array1::from(extended), SAMPLE_RATE);
+
+        let alignment = align(&radio_edit, &extended, &options).unwrap();
+        assert!(alignment.is_match(&options), "{alignment:?}");
+        assert!((alignment.candidate_start.as_secs_f32() - 10.0).abs() < 1.5);
+        assert!((alignment.candidate_end.as_secs_f32() - 45.2).abs() < 1.5);
+        assert!(alignment.reference_start.is_zero());
+
+        // the reverse direction aligns the shorter radio edit within the reference
+        let reverse = align(&extended, &radio_edit, &options).unwrap();
+        assert!(reverse.is_match(&options));
+        assert!((reverse.reference_start.as_secs_f32() - 10.0).abs() < 1.5);
+
+        let unrelated = song(&[(d_minor, 6.0), (e, 6.0), (a_minor, 6.0), (d_minor, 6.0)]);
+        let mismatch = align(&unrelated, &extended, &options).unwrap();
+        assert!(!mismatch.is_match(&options), "{mismatch:?}");
+        assert!(mismatch.similarity < alignment.similarity);
+    }
+}
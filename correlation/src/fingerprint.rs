@@ -0,0 +1,308 @@
+use crate::decode::Samples;
+use crate::spectrum::Spectrogram;
+use anyhow::Result;
+use ndarray::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FingerprintOptions {
+    /// Sample rate the audio is resampled to before analysis
+    pub sample_rate: u32,
+    pub window: usize,
+    pub hop: usize,
+    /// Frequency neighbourhood (in bins) a spectral peak must dominate
+    pub peak_bins: usize,
+    /// Time neighbourhood (in frames) a spectral peak must dominate
+    pub peak_frames: usize,
+    /// Maximum number of peaks paired with each anchor peak
+    pub fan_out: usize,
+    /// Maximum distance (in frames) between paired peaks
+    pub max_pair_frames: usize,
+}
+
+impl Default for FingerprintOptions {
+    fn default() -> Self {
+        Self {
+            sample_rate: 11_025,
+            window: 1024,
+            hop: 256,
+            peak_bins: 12,
+            peak_frames: 8,
+            fan_out: 5,
+            max_pair_frames: 63,
+        }
+    }
+}
+
+/// Hash of a pair of spectral peaks anchored at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Landmark {
+    pub hash: u32,
+    /// Frame of the anchor peak
+    pub frame: u32,
+}
+
+/// Compact landmark fingerprint of a track
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// Duration of one frame in seconds
+    pub frame_secs: f64,
+    pub landmarks: Vec<Landmark>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FingerprintMatch {
+    /// Offset of the second fingerprint within the first in seconds
+    ///
+    /// Negative if the second fingerprint starts before the first one.
+    pub offset_secs: f64,
+    /// Number of landmarks that agree on the offset
+    pub matches: usize,
+    /// Fraction of the landmarks of the shorter fingerprint that match in [0, 1]
+    pub similarity: f32,
+}
+
+/// Local maxima of the spectrogram that dominate their neighbourhood
+fn spectral_peaks(spectrogram: &Spectrogram, options: &FingerprintOptions) -> Vec<(usize, usize)> {
+    let (frames, bins) = spectrogram.data.dim();
+    let data = spectrogram.data.mapv(|v| (1.0 + v).ln());
+
+    // separable maximum filter over frequency and time
+    let mut freq_max = Array2::<f32>::zeros((frames, bins));
+    for (frame, row) in data.axis_iter(Axis(0)).enumerate() {
+        for bin in 0..bins {
+            let lo = bin.saturating_sub(options.peak_bins);
+            let hi = (bin + options.peak_bins + 1).min(bins);
+            freq_max[[frame, bin]] = row.slice(s![lo..hi]).fold(0f32, |acc, v| acc.max(*v));
+        }
+    }
+    let mut peaks = Vec::new();
+    for frame in 0..frames {
+        let lo = frame.saturating_sub(options.peak_frames);
+        let hi = (frame + options.peak_frames + 1).min(frames);
+        let mean = data.row(frame).mean().unwrap_or(0.0);
+        for bin in 1..bins {
+            let value = data[[frame, bin]];
+            if value <= mean {
+                continue;
+            }
+            let neighbourhood = freq_max
+                .slice(s![lo..hi, bin])
+                .fold(0f32, |acc, v| acc.max(*v));
+            if value >= neighbourhood {
+                peaks.push((frame, bin));
+            }
+        }
+    }
+    peaks
+}
+
+/// Bit layout of a landmark hash, sized to the spectrogram and the pair distance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HashLayout {
+    bin_bits: u32,
+    delta_bits: u32,
+}
+
+impl HashLayout {
+    /// Layout that fits every bin and frame distance into distinct bits
+    ///
+    /// # Panics
+    /// If the bins of both peaks and the distance do not fit into 32 bits.
+    fn new(bins: usize, max_pair_frames: usize) -> Self {
+        // bits needed for the values below `count`
+        let bits = |count: usize| usize::BITS - count.saturating_sub(1).leading_zeros();
+        let layout = Self {
+            bin_bits: bits(bins),
+            delta_bits: bits(max_pair_frames + 1),
+        };
+        assert!(
+            2 * layout.bin_bits + layout.delta_bits <= u32::BITS,
+            "{bins} bins and pairs up to {max_pair_frames} frames apart do not fit a 32 bit hash"
+        );
+        layout
+    }
+
+    fn hash(&self, anchor_bin: usize, target_bin: usize, delta: usize) -> u32 {
+        ((anchor_bin as u32) << (self.bin_bits + self.delta_bits))
+            | ((target_bin as u32) << self.delta_bits)
+            | delta as u32
+    }
+}
+
+impl Fingerprint {
+    /// Computes a landmark fingerprint from peak pairs of the spectrogram
+    #[must_use]
+    pub fn new(samples: &Samples, options: &FingerprintOptions) -> Self {
+        let samples = samples.resample(options.sample_rate);
+        let spectrogram = Spectrogram::new(&samples, options.window, options.hop);
+        let peaks = spectral_peaks(&spectrogram, options);
+        let layout = HashLayout::new(spectrogram.bins(), options.max_pair_frames);
+
+        let mut landmarks = Vec::new();
+        for (i, (anchor_frame, anchor_bin)) in peaks.iter().enumerate() {
+            let targets = peaks[i + 1..]
+                .iter()
+                .filter(|(frame, _)| frame > anchor_frame)
+                .take_while(|(frame, _)| frame - anchor_frame <= options.max_pair_frames)
+                .take(options.fan_out);
+            for (target_frame, target_bin) in targets {
+                landmarks.push(Landmark {
+                    hash: layout.hash(*anchor_bin, *target_bin, target_frame - anchor_frame),
+                    frame: *anchor_frame as u32,
+                });
+            }
+        }
+
+        Self {
+            frame_secs: spectrogram.hop_secs(),
+            landmarks,
+        }
+    }
+
+    /// Decodes a file and computes its fingerprint
+    ///
+    /// # Errors
+    /// If the file cannot be decoded.
+    pub fn from_file(path: impl AsRef<Path>, options: &FingerprintOptions) -> Result<Self> {
+        let samples = Samples::from_file(path)?;
+        Ok(Self::new(&samples, options))
+    }
+
+    /// Compares two fingerprints by voting for the time offset of matching hashes
+    ///
+    /// Hashes are only comparable between fingerprints with the same frame duration,
+    /// i.e. computed with the same options, otherwise nothing matches.
+    #[must_use]
+    pub fn compare(&self, other: &Self) -> FingerprintMatch {
+        if (self.frame_secs - other.frame_secs).abs() > f64::EPSILON {
+            return FingerprintMatch::default();
+        }
+        let mut index: HashMap<u32, Vec<u32>> = HashMap::new();
+        for landmark in &self.landmarks {
+            index.entry(landmark.hash).or_default().push(landmark.frame);
+        }
+
+        let mut votes: HashMap<i64, usize> = HashMap::new();
+        for landmark in &other.landmarks {
+            if let Some(frames) = index.get(&landmark.hash) {
+                for frame in frames {
+                    *votes
+                        .entry(i64::from(*frame) - i64::from(landmark.frame))
+                        .or_default() += 1;
+                }
+            }
+        }
+
+        // tolerate off by one frame offsets
+        let best = votes
+            .keys()
+            .map(|offset| {
+                let count = (offset - 1..=offset + 1)
+                    .filter_map(|o| votes.get(&o))
+                    .sum::<usize>();
+                (*offset, count)
+            })
+            .max_by_key(|(offset, count)| (*count, -offset.abs()));
+
+        let Some((offset, matches)) = best else {
+            return FingerprintMatch::default();
+        };
+        let total = self.landmarks.len().min(other.landmarks.len()).max(1);
+        FingerprintMatch {
+            offset_secs: offset as f64 * self.frame_secs,
+            matches,
+            similarity: (matches as f32 / total as f32).min(1.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fingerprint, FingerprintOptions, HashLayout};
+    use crate::decode::Samples;
+    use approx::assert_abs_diff_eq;
+    use ndarray::prelude::*;
+
+    /// Sequence of random pure tones
+    fn melody(len: usize, sample_rate: u32, seed: u64) -> Array1<f32> {
+        let mut state = seed;
+        let note = sample_rate as usize / 8;
+        let mut freq = 0f32;
+        Array::from_iter((0..len).map(|i| {
+            if i % note == 0 {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                freq = 200.0 + (state >> 40) as f32 % 3000.0;
+            }
+            (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin()
+        }))
+    }
+
+    #[test]
+    fn test_fingerprint_match() {
+        let sample_rate = 11_025;
+        let track = Samples::new(
+            melody(sample_rate as usize * 30, sample_rate, 1),
+            sample_rate,
+        );
+        let clip = Samples::new(
+            track.data.slice(s![sample_rate as usize * 10..]).to_owned(),
+            sample_rate,
+        );
+        let other = Samples::new(
+            melody(sample_rate as usize * 20, sample_rate, 2),
+            sample_rate,
+        );
+
+        let options = FingerprintOptions::default();
+        let track = Fingerprint::new(&track, &options);
+        let clip = Fingerprint::new(&clip, &options);
+        let other = Fingerprint::new(&other, &options);
+
+        let matched = track.compare(&clip);
+        assert_abs_diff_eq!(matched.offset_secs, 10.0, epsilon = 0.05);
+        assert!(matched.similarity > 0.2);
+        assert!(track.compare(&other).similarity < 0.05);
+    }
+
+    #[test]
+    fn test_fingerprint_match_across_sample_rates() {
+        let sample_rate = 48_000;
+        let track = Samples::new(
+            melody(sample_rate as usize * 30, sample_rate, 1),
+            sample_rate,
+        );
+        // e.g. a 44.1 kHz preview of a 48 kHz download
+        let clip = Samples::new(
+            track.data.slice(s![sample_rate as usize * 10..]).to_owned(),
+            sample_rate,
+        )
+        .resample(44_100);
+
+        let options = FingerprintOptions::default();
+        let track = Fingerprint::new(&track, &options);
+        let clip = Fingerprint::new(&clip, &options);
+        assert_eq!(track.frame_secs, clip.frame_secs);
+
+        let matched = track.compare(&clip);
+        assert_abs_diff_eq!(matched.offset_secs, 10.0, epsilon = 0.05);
+        assert!(matched.similarity > 0.2);
+    }
+
+    #[test]
+    fn test_hash_layout_fits_all_bins() {
+        let options = FingerprintOptions::default();
+        let layout = HashLayout::new(options.window / 2 + 1, options.max_pair_frames);
+        assert_eq!((layout.bin_bits, layout.delta_bits), (10, 6));
+
+        // bins above 1023 of a longer window must not collide with lower bins
+        let layout = HashLayout::new(4096 / 2 + 1, options.max_pair_frames);
+        assert_ne!(layout.hash(1124, 200, 3), layout.hash(100, 200, 3));
+        assert_ne!(layout.hash(200, 1124, 3), layout.hash(200, 100, 3));
+        assert_ne!(layout.hash(200, 100, 63), layout.hash(201, 100, 63));
+    }
+}
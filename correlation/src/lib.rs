@@ -1,5 +1,23 @@
+pub mod analysis;
+pub mod decode;
+pub mod dtw;
+pub mod fingerprint;
+pub mod ncc;
+pub mod sanity;
+pub mod search;
+pub mod spectrum;
+pub mod verify;
+
+pub use analysis::{AnalysisOptions, Loudness, TrackAnalysis};
+pub use decode::Samples;
+pub use dtw::{align, align_files, Alignment, DtwOptions};
+pub use fingerprint::{Fingerprint, FingerprintMatch, FingerprintOptions};
+pub use ncc::{normalized_cross_correlation, Peak};
+pub use sanity::{check_file, Issue, SanityOptions, SanityReport};
+pub use search::{coarse_to_fine, SearchOptions};
+pub use verify::{verify, verify_files, Verification, VerifyOptions};
+
 use anyhow::Result;
-use ndarray::parallel::prelude::*;
 use ndarray::prelude::*;
 use ndarray::Array;
 use num::pow::pow;
@@ -10,7 +28,6 @@ use std::fs::File;
 use std::io::BufReader;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::thread;
 use std::time::Instant;
 
 pub fn get_source_from_file(
@@ -45,8 +62,7 @@ where
     }
 }
 
-#[must_use]
-pub fn fftconvolve<T>(volume: &Array1<T>, kernel: &Array1<T>) -> Array1<T>
+fn fftconvolve_complex<T>(volume: &Array1<T>, kernel: &Array1<T>) -> Array1<Complex<T>>
 where
     T: FromPrimitive + Signed + Float + FloatConst + std::fmt::Debug + Sync + Send + 'static,
 {
@@ -56,18 +72,12 @@ where
     let fft = Arc::new(Radix4::new(size2, FftDirection::Forward));
     let inverse_fft = Radix4::new(size2, FftDirection::Inverse);
 
-    let volume_fft = fft.clone();
     let volume_size = volume.len();
-    let volume = volume.to_owned();
-    let volume_fft_handle = thread::spawn(move || padded_fft(&volume, volume_fft, size2));
-
-    let kernel_fft = fft;
     let kernel_size = kernel.len();
-    let kernel = kernel.to_owned();
-    let kernel_fft_handle = thread::spawn(move || padded_fft(&kernel, kernel_fft, size2));
-
-    let volume_fft = volume_fft_handle.join().unwrap();
-    let kernel_fft = kernel_fft_handle.join().unwrap();
+    let (volume_fft, kernel_fft) = rayon::join(
+        || padded_fft(volume, fft.clone(), size2),
+        || padded_fft(kernel, fft.clone(), size2),
+    );
 
     let _start = Instant::now();
     let mut ret_fft = volume_fft * kernel_fft;
@@ -85,11 +95,28 @@ where
     let scale = T::from(1.0 / ret_fft.len() as f32).unwrap();
     let ret_fft = ret_fft
         .slice(s![valid_start..valid_end])
-        .map(|v| T::from(v.scale(scale).norm()).unwrap());
+        .map(|v| v.scale(scale));
     assert!(ret_fft.len() == valid_len);
     ret_fft
 }
 
+#[must_use]
+pub fn fftconvolve<T>(volume: &Array1<T>, kernel: &Array1<T>) -> Array1<T>
+where
+    T: FromPrimitive + Signed + Float + FloatConst + std::fmt::Debug + Sync + Send + 'static,
+{
+    fftconvolve_complex(volume, kernel).map(|v| T::from(v.norm()).unwrap())
+}
+
+/// Like `fftconvolve`, but keeps the sign of the (real valued) result
+#[must_use]
+pub fn fftconvolve_signed<T>(volume: &Array1<T>, kernel: &Array1<T>) -> Array1<T>
+where
+    T: FromPrimitive + Signed + Float + FloatConst + std::fmt::Debug + Sync + Send + 'static,
+{
+    fftconvolve_complex(volume, kernel).map(|v| v.re)
+}
+
 #[must_use]
 pub fn correlate<T>(volume: &Array1<T>, kernel: &Array1<T>) -> (Array1<T>, (usize, T))
 where
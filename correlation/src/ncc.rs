@@ -0,0 +1,140 @@
+use crate::fftconvolve_signed;
+use ndarray::prelude::*;
+use ndarray::Array;
+use num::traits::{Float, FloatConst, FromPrimitive, Signed};
+
+/// Normalized cross correlation of a kernel at every valid lag of a volume
+///
+/// Both the kernel and each window of the volume are normalized to zero mean
+/// and unit variance, so the result is the Pearson correlation per lag in
+/// [-1, 1] and independent of the signal energy.
+#[must_use]
+pub fn normalized_cross_correlation<T>(volume: &Array1<T>, kernel: &Array1<T>) -> Array1<T>
+where
+    T: FromPrimitive + Signed + Float + FloatConst + std::fmt::Debug + Sync + Send + 'static,
+{
+    let m = kernel.len();
+    if m == 0 || volume.len() < m {
+        return Array1::zeros(0);
+    }
+    let len = T::from_usize(m).unwrap();
+    let kernel_mean = kernel.sum() / len;
+    let kernel = kernel.mapv(|v| v - kernel_mean);
+    let kernel_norm = kernel.mapv(|v| v * v).sum().sqrt();
+
+    // the kernel has zero mean, hence the window mean does not affect the numerator
+    let reversed = kernel.slice(s![..;-1]).to_owned();
+    let numerator = fftconvolve_signed(volume, &reversed);
+
+    // running sums for the variance of each window of the volume, accumulated in f64
+    // as the differences of f32 sums over millions of samples lose all precision
+    let mut sum = Vec::with_capacity(volume.len() + 1);
+    let mut sum_squared = Vec::with_capacity(volume.len() + 1);
+    sum.push(0f64);
+    sum_squared.push(0f64);
+    for (i, v) in volume.iter().enumerate() {
+        let v = v.to_f64().unwrap_or(0.0);
+        sum.push(sum[i] + v);
+        sum_squared.push(sum_squared[i] + v * v);
+    }
+
+    let window_len = m as f64;
+    Array::from_iter(numerator.iter().enumerate().map(|(lag, num)| {
+        let s1 = sum[lag + m] - sum[lag];
+        let s2 = sum_squared[lag + m] - sum_squared[lag];
+        let variance = (s2 - s1 * s1 / window_len).max(0.0);
+        let denominator = kernel_norm * T::from_f64(variance.sqrt()).unwrap();
+        if denominator > T::epsilon() {
+            (*num / denominator).max(-T::one()).min(T::one())
+        } else {
+            T::zero()
+        }
+    }))
+}
+
+/// Correlation peak with metrics describing how distinct it is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Peak<T> {
+    pub lag: usize,
+    pub value: T,
+    /// Distance of the peak from the mean of the sidelobes in standard deviations
+    pub peak_to_sidelobe: T,
+    /// Ratio of the peak to the median absolute correlation
+    pub peak_to_median: T,
+}
+
+impl<T> Peak<T>
+where
+    T: FromPrimitive + Float + std::fmt::Debug,
+{
+    /// Finds the maximum of a correlation and computes its peak ratios
+    ///
+    /// Values within `exclusion` lags of the peak are not considered sidelobes.
+    #[must_use]
+    pub fn find(correlation: &Array1<T>, exclusion: usize) -> Option<Self> {
+        let (lag, value) = correlation.indexed_iter().fold(
+            None,
+            |best: Option<(usize, T)>, (i, v)| match best {
+                Some((_, best_value)) if best_value >= *v => best,
+                _ => Some((i, *v)),
+            },
+        )?;
+
+        let sidelobes: Vec<T> = correlation
+            .indexed_iter()
+            .filter(|(i, _)| i.abs_diff(lag) > exclusion)
+            .map(|(_, v)| *v)
+            .collect();
+
+        let peak_to_sidelobe = if sidelobes.is_empty() {
+            T::zero()
+        } else {
+            let n = T::from_usize(sidelobes.len()).unwrap();
+            let mean = sidelobes.iter().fold(T::zero(), |acc, v| acc + *v) / n;
+            let variance = sidelobes
+                .iter()
+                .fold(T::zero(), |acc, v| acc + (*v - mean) * (*v - mean))
+                / n;
+            let std = variance.sqrt();
+            if std > T::epsilon() {
+                (value - mean) / std
+            } else {
+                T::zero()
+            }
+        };
+
+        let mut magnitudes: Vec<T> = correlation.iter().map(|v| v.abs()).collect();
+        magnitudes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let median = magnitudes[magnitudes.len() / 2];
+        let peak_to_median = if median > T::epsilon() {
+            value / median
+        } else {
+            T::zero()
+        };
+
+        Some(Self {
+            lag,
+            value,
+            peak_to_sidelobe,
+            peak_to_median,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalized_cross_correlation, Peak};
+    use approx::assert_abs_diff_eq;
+    use ndarray::prelude::*;
+
+    #[test]
+    fn test_ncc_is_scale_invariant() {
+        let within = Array::from_iter(0..500).mapv(|v: i32| (f64::from(v).powi(2) * 0.002).sin());
+        let find = within.slice(s![120..220]).mapv(|v| v * 10.0 + 3.0);
+        let ncc = normalized_cross_correlation(&within, &find);
+        let peak = Peak::find(&ncc, 5).unwrap();
+        assert_eq!(peak.lag, 120);
+        assert_abs_diff_eq!(peak.value, 1.0, epsilon = 1e-6);
+        assert!(ncc.iter().all(|v| (-1.0..=1.0).contains(v)));
+    }
+}
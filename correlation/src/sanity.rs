@@ -0,0 +1,332 @@
+use crate::decode::Samples;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+/// Rate of the blocks whose level is compared against the silence threshold
+const BLOCKS_PER_SEC: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SanityOptions {
+    /// Level in dBFS below which a block is considered silent
+    pub silence_threshold_db: f32,
+    /// Minimum length of a silent gap within the track to be reported
+    pub min_gap: Duration,
+    /// Maximum length of a silent gap within the track
+    pub max_gap: Duration,
+    /// Maximum length of the silence at the start or the end of the track
+    pub max_edge_silence: Duration,
+    /// Maximum ratio of silent audio
+    pub max_silent_ratio: f32,
+    /// Absolute amplitude at or above which a sample is considered clipped
+    pub clip_threshold: f32,
+    /// Minimum number of consecutive clipped samples, as single full scale
+    /// samples can occur in well mastered tracks
+    pub min_clip_run: usize,
+    /// Maximum ratio of clipped samples
+    pub max_clipped_ratio: f32,
+    /// Maximum absolute difference between decoded and expected duration
+    pub duration_tolerance: Duration,
+}
+
+impl Default for SanityOptions {
+    fn default() -> Self {
+        Self {
+            silence_threshold_db: -60.0,
+            min_gap: Duration::from_secs(2),
+            max_gap: Duration::from_secs(10),
+            max_edge_silence: Duration::from_secs(10),
+            max_silent_ratio: 0.5,
+            clip_threshold: 0.999,
+            min_clip_run: 3,
+            max_clipped_ratio: 0.001,
+            duration_tolerance: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SilentGap {
+    pub start: Duration,
+    pub duration: Duration,
+}
+
+/// Reason for a track to fail the sanity check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Issue {
+    /// The decoded duration does not match the expected duration, e.g. when truncated
+    DurationMismatch {
+        decoded: Duration,
+        expected: Duration,
+    },
+    LeadingSilence(Duration),
+    TrailingSilence(Duration),
+    SilentGap(SilentGap),
+    MostlySilent {
+        silent_ratio: f32,
+    },
+    Clipped {
+        clipped_ratio: f32,
+    },
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DurationMismatch { decoded, expected } => write!(
+                f,
+                "decoded duration of {:.1}s does not match expected duration of {:.1}s",
+                decoded.as_secs_f32(),
+                expected.as_secs_f32()
+            ),
+            Self::LeadingSilence(duration) => {
+                write!(f, "{:.1}s of leading silence", duration.as_secs_f32())
+            }
+            Self::TrailingSilence(duration) => {
+                write!(f, "{:.1}s of trailing silence", duration.as_secs_f32())
+            }
+            Self::SilentGap(gap) => write!(
+                f,
+                "{:.1}s of silence at {:.1}s",
+                gap.duration.as_secs_f32(),
+                gap.start.as_secs_f32()
+            ),
+            Self::MostlySilent { silent_ratio } => {
+                write!(f, "{:.0}% of the track is silent", silent_ratio * 100.0)
+            }
+            Self::Clipped { clipped_ratio } => {
+                write!(
+                    f,
+                    "{:.2}% of the samples are clipped",
+                    clipped_ratio * 100.0
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SanityReport {
+    pub duration: Duration,
+    pub expected_duration: Option<Duration>,
+    pub leading_silence: Duration,
+    pub trailing_silence: Duration,
+    /// Silent gaps within the track of at least the minimum gap length
+    pub silent_gaps: Vec<SilentGap>,
+    pub silent_ratio: f32,
+    pub clipped_ratio: f32,
+    /// Reasons for failing the check, empty if the track passed
+    pub issues: Vec<Issue>,
+}
+
+impl SanityReport {
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Ratio of samples that are part of a run of clipped samples
+fn clipped_ratio(samples: &Samples, options: &SanityOptions) -> f32 {
+    let mut clipped = 0usize;
+    let mut run = 0usize;
+    for v in samples.data.iter().chain(std::iter::once(&0.0)) {
+        if v.abs() >= options.clip_threshold {
+            run += 1;
+        } else {
+            if run >= options.min_clip_run {
+                clipped += run;
+            }
+            run = 0;
+        }
+    }
+    clipped as f32 / samples.len().max(1) as f32
+}
+
+/// Checks decoded audio for truncation, silence and clipping
+///
+/// The expected duration is usually the duration of the track
+/// reported by the source, e.g. `Track::duration_millis`.
+#[must_use]
+pub fn check(
+    samples: &Samples,
+    expected_duration: Option<Duration>,
+    options: &SanityOptions,
+) -> SanityReport {
+    let block = (samples.sample_rate / BLOCKS_PER_SEC).max(1) as usize;
+    let block_duration = |blocks: usize| {
+        Duration::from_secs_f64((blocks * block) as f64 / f64::from(samples.sample_rate.max(1)))
+    };
+    let threshold = 10f32.powf(options.silence_threshold_db / 20.0);
+    let silent: Vec<bool> = samples
+        .data
+        .exact_chunks(block)
+        .into_iter()
+        .map(|chunk| chunk.mapv(|v| v * v).mean().unwrap_or(0.0).sqrt() < threshold)
+        .collect();
+
+    let leading = silent.iter().take_while(|s| **s).count();
+    let trailing = if leading == silent.len() {
+        0
+    } else {
+        silent.iter().rev().take_while(|s| **s).count()
+    };
+    let mut silent_gaps = Vec::new();
+    let mut run = 0;
+    for (i, s) in silent[..silent.len() - trailing]
+        .iter()
+        .enumerate()
+        .skip(leading)
+    {
+        if *s {
+            run += 1;
+            continue;
+        }
+        if run > 0 && block_duration(run) >= options.min_gap {
+            silent_gaps.push(SilentGap {
+                start: block_duration(i - run),
+                duration: block_duration(run),
+            });
+        }
+        run = 0;
+    }
+    let silent_ratio = if silent.is_empty() {
+        1.0
+    } else {
+        silent.iter().filter(|s| **s).count() as f32 / silent.len() as f32
+    };
+
+    let duration = samples.duration();
+    let leading_silence = block_duration(leading);
+    let trailing_silence = block_duration(trailing);
+    let clipped_ratio = clipped_ratio(samples, options);
+
+    let mut issues = Vec::new();
+    if let Some(expected) = expected_duration {
+        if duration.abs_diff(expected) > options.duration_tolerance {
+            issues.push(Issue::DurationMismatch {
+                decoded: duration,
+                expected,
+            });
+        }
+    }
+    if silent_ratio > options.max_silent_ratio {
+        issues.push(Issue::MostlySilent { silent_ratio });
+    } else {
+        if leading_silence > options.max_edge_silence {
+            issues.push(Issue::LeadingSilence(leading_silence));
+        }
+        if trailing_silence > options.max_edge_silence {
+            issues.push(Issue::TrailingSilence(trailing_silence));
+        }
+        issues.extend(
+            silent_gaps
+                .iter()
+                .filter(|gap| gap.duration > options.max_gap)
+                .map(|gap| Issue::SilentGap(*gap)),
+        );
+    }
+    if clipped_ratio > options.max_clipped_ratio {
+        issues.push(Issue::Clipped { clipped_ratio });
+    }
+
+    SanityReport {
+        duration,
+        expected_duration,
+        leading_silence,
+        trailing_silence,
+        silent_gaps,
+        silent_ratio,
+        clipped_ratio,
+        issues,
+    }
+}
+
+/// Decodes and checks a downloaded file
+///
+/// # Errors
+/// If the file cannot be decoded.
+pub fn check_file(
+    path: impl AsRef<Path>,
+    expected_duration: Option<Duration>,
+    options: &SanityOptions,
+) -> Result<SanityReport> {
+    let samples = Samples::from_file(path)?;
+    Ok(check(&samples, expected_duration, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, Issue, SanityOptions, SilentGap};
+    use crate::decode::Samples;
+    use crate::verify::tests::noise;
+    use ndarray::prelude::*;
+    use std::time::Duration;
+
+    const SAMPLE_RATE: u32 = 8_000;
+
+    fn track(sections: &[(f32, f32)]) -> Samples {
+        let mut data = Vec::new();
+        for (i, (secs, amplitude)) in sections.iter().enumerate() {
+            let len = (secs * SAMPLE_RATE as f32) as usize;
+            data.extend(noise(len, i as u64).mapv(|v| amplitude * v).iter());
+        }
+        Samples::new(Array1::from(data), SAMPLE_RATE)
+    }
+
+    #[test]
+    fn test_sanity_passes() {
+        let samples = track(&[(1.0, 0.0), (60.0, 0.5), (3.0, 0.0), (60.0, 0.5), (1.0, 0.0)]);
+        let report = check(
+            &samples,
+            Some(Duration::from_secs(124)),
+            &SanityOptions::default(),
+        );
+        assert!(report.passed(), "{:?}", report.issues);
+        assert_eq!(report.leading_silence, Duration::from_secs(1));
+        assert_eq!(report.trailing_silence, Duration::from_secs(1));
+        assert_eq!(
+            report.silent_gaps,
+            vec![SilentGap {
+                start: Duration::from_secs(61),
+                duration: Duration::from_secs(3),
+            }]
+        );
+        assert!(report.clipped_ratio < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_sanity_fails() {
+        let options = SanityOptions::default();
+
+        // cut off halfway
+        let truncated = track(&[(60.0, 0.5)]);
+        let report = check(&truncated, Some(Duration::from_secs(120)), &options);
+        assert!(!report.passed());
+        assert!(matches!(
+            report.issues.as_slice(),
+            [Issue::DurationMismatch { .. }]
+        ));
+
+        // mostly silent
+        let silent = track(&[(20.0, 0.5), (100.0, 0.0)]);
+        let report = check(&silent, Some(Duration::from_secs(120)), &options);
+        assert!(matches!(
+            report.issues.as_slice(),
+            [Issue::MostlySilent { .. }]
+        ));
+        assert_eq!(report.trailing_silence, Duration::from_secs(100));
+
+        // heavily clipped
+        let clipped = Samples::new(
+            track(&[(60.0, 4.0)]).data.mapv(|v| v.clamp(-1.0, 1.0)),
+            SAMPLE_RATE,
+        );
+        let report = check(&clipped, None, &options);
+        assert!(matches!(report.issues.as_slice(), [Issue::Clipped { .. }]));
+        assert!(report.issues[0]
+            .to_string()
+            .ends_with("of the samples are clipped"));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::ncc::{normalized_cross_correlation, Peak};
+use ndarray::prelude::*;
+use ndarray::Array;
+use num::traits::{Float, FloatConst, FromPrimitive, Signed};
+use rayon::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Minimum downsampling factor of the coarse search
+    pub factor: usize,
+    /// Upper bound for the length of the downsampled volume
+    ///
+    /// Long volumes are downsampled further to bound the size of the coarse FFT.
+    pub max_coarse_len: usize,
+    /// Number of coarse peaks that are refined at full resolution
+    pub candidates: usize,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            factor: 8,
+            max_coarse_len: 1 << 16,
+            candidates: 3,
+        }
+    }
+}
+
+/// Downsamples a signal by averaging blocks of `factor` values
+#[must_use]
+pub fn downsample<T>(data: &Array1<T>, factor: usize) -> Array1<T>
+where
+    T: FromPrimitive + Float,
+{
+    let factor = factor.max(1);
+    let scale = T::from_usize(factor).unwrap();
+    Array::from_iter(
+        data.exact_chunks(factor)
+            .into_iter()
+            .map(|chunk| chunk.fold(T::zero(), |acc, v| acc + *v) / scale),
+    )
+}
+
+/// Lags of the `n` highest local maxima that are at least `exclusion` lags apart
+fn top_peaks<T>(correlation: &Array1<T>, n: usize, exclusion: usize) -> Vec<usize>
+where
+    T: Float,
+{
+    let mut order: Vec<usize> = (0..correlation.len()).collect();
+    order.sort_by(|a, b| {
+        correlation[*b]
+            .partial_cmp(&correlation[*a])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut peaks: Vec<usize> = Vec::with_capacity(n);
+    for lag in order {
+        if peaks.len() >= n {
+            break;
+        }
+        if peaks.iter().all(|peak| peak.abs_diff(lag) > exclusion) {
+            peaks.push(lag);
+        }
+    }
+    peaks
+}
+
+/// Finds a kernel in a long volume using a coarse to fine search
+///
+/// The normalized cross correlation is first computed on heavily downsampled
+/// signals. Only the best coarse peaks are then refined at full resolution
+/// in a small window around them, which keeps memory bounded by the kernel
+/// size rather than the volume size.
+///
+/// The peak ratios of the returned peak are those of the coarse correlation,
+/// as only it covers all lags.
+#[must_use]
+pub fn coarse_to_fine<T>(
+    volume: &Array1<T>,
+    kernel: &Array1<T>,
+    options: &SearchOptions,
+) -> Option<Peak<T>>
+where
+    T: FromPrimitive + Signed + Float + FloatConst + std::fmt::Debug + Sync + Send + 'static,
+{
+    if kernel.is_empty() || volume.len() < kernel.len() {
+        return None;
+    }
+    let factor = options
+        .factor
+        .max(volume.len().div_ceil(options.max_coarse_len.max(1)))
+        .min(kernel.len())
+        .max(1);
+
+    let coarse_volume = downsample(volume, factor);
+    let coarse_kernel = downsample(kernel, factor);
+    let coarse = normalized_cross_correlation(&coarse_volume, &coarse_kernel);
+    let coarse_peak = Peak::find(&coarse, 1)?;
+    let candidates = top_peaks(&coarse, options.candidates.max(1), 1);
+
+    let max_lag = volume.len() - kernel.len();
+    let refined = candidates
+        .par_iter()
+        .filter_map(|coarse_lag| {
+            // the true lag is within one coarse block of the coarse lag
+            let start = (coarse_lag * factor).saturating_sub(factor).min(max_lag);
+            let end = (coarse_lag * factor + factor).min(max_lag);
+            let window = volume.slice(s![start..end + kernel.len()]).to_owned();
+            let fine = normalized_cross_correlation(&window, kernel);
+            Peak::find(&fine, 0).map(|peak| (start + peak.lag, peak.value))
+        })
+        .reduce_with(|a, b| if b.1 > a.1 { b } else { a });
+
+    let (lag, value) = refined?;
+    Some(Peak {
+        lag,
+        value,
+        ..coarse_peak
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{coarse_to_fine, SearchOptions};
+    use ndarray::prelude::*;
+
+    #[test]
+    fn test_coarse_to_fine() {
+        let within = Array::from_iter(0..20_000).mapv(|v: i32| {
+            let v = f64::from(v);
+            (v * 0.002).sin() + (v.powf(1.1) * 0.003).sin()
+        });
+        let find = within.slice(s![12_345..14_345]).to_owned();
+        let options = SearchOptions {
+            max_coarse_len: 1024,
+            ..SearchOptions::default()
+        };
+        let peak = coarse_to_fine(&within, &find, &options).unwrap();
+        assert_eq!(peak.lag, 12_345);
+        assert!(peak.value > 0.99);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::decode::Samples;
+use ndarray::prelude::*;
+use ndarray::Array;
+use rayon::prelude::*;
+use rustfft::{num_complex::Complex, FftPlanner};
+use std::f32::consts::PI;
+
+/// Magnitude spectrogram of a mono signal
+#[derive(Debug, Clone)]
+pub struct Spectrogram {
+    /// Magnitudes with shape (frames, bins)
+    pub data: Array2<f32>,
+    pub sample_rate: u32,
+    pub window: usize,
+    pub hop: usize,
+}
+
+impl Spectrogram {
+    /// Computes the short time fourier transform using a hann window
+    #[must_use]
+    pub fn new(samples: &Samples, window: usize, hop: usize) -> Self {
+        let hop = hop.max(1);
+        let bins = window / 2 + 1;
+        let frames = if samples.len() >= window {
+            (samples.len() - window) / hop + 1
+        } else {
+            0
+        };
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(window);
+        let hann = hann_window(window);
+
+        let mut data = Array2::<f32>::zeros((frames, bins));
+        data.axis_iter_mut(Axis(0))
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(frame, mut row)| {
+                let start = frame * hop;
+                let mut buffer: Vec<Complex<f32>> = samples
+                    .data
+                    .slice(s![start..start + window])
+                    .iter()
+                    .zip(hann.iter())
+                    .map(|(v, w)| Complex { re: v * w, im: 0.0 })
+                    .collect();
+                fft.process(&mut buffer);
+                for (out, v) in row.iter_mut().zip(buffer.iter()) {
+                    *out = v.norm();
+                }
+            });
+
+        Self {
+            data,
+            sample_rate: samples.sample_rate,
+            window,
+            hop,
+        }
+    }
+
+    #[must_use]
+    pub fn frames(&self) -> usize {
+        self.data.nrows()
+    }
+
+    #[must_use]
+    pub fn bins(&self) -> usize {
+        self.data.ncols()
+    }
+
+    /// Center frequency of a bin in Hz
+    #[must_use]
+    pub fn bin_frequency(&self, bin: usize) -> f32 {
+        bin as f32 * self.sample_rate as f32 / self.window as f32
+    }
+
+    /// Duration of one hop in seconds
+    #[must_use]
+    pub fn hop_secs(&self) -> f64 {
+        self.hop as f64 / f64::from(self.sample_rate)
+    }
+}
+
+#[must_use]
+pub fn hann_window(len: usize) -> Array1<f32> {
+    Array::from_iter((0..len).map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / len as f32).cos()))
+}
@@ -0,0 +1,183 @@
+use crate::decode::Samples;
+use crate::search::{coarse_to_fine, SearchOptions};
+use anyhow::Result;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyOptions {
+    /// Rate of the amplitude envelopes that are correlated
+    pub envelope_rate: u32,
+    /// Minimum confidence for a candidate to be considered a match
+    pub min_confidence: f32,
+    /// Minimum peak to sidelobe ratio for a candidate to be considered a match
+    pub min_peak_to_sidelobe: f32,
+    pub search: SearchOptions,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self {
+            envelope_rate: 200,
+            min_confidence: 0.6,
+            min_peak_to_sidelobe: 5.0,
+            search: SearchOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Verification {
+    /// Offset of the reference clip within the candidate
+    pub offset: Duration,
+    /// Normalized cross correlation at the offset, clamped to [0, 1]
+    pub confidence: f32,
+    /// How distinct the correlation peak is from all other offsets
+    pub peak_to_sidelobe: f32,
+    pub peak_to_median: f32,
+}
+
+impl Verification {
+    #[must_use]
+    pub fn is_match(&self, options: &VerifyOptions) -> bool {
+        self.confidence >= options.min_confidence
+            && self.peak_to_sidelobe >= options.min_peak_to_sidelobe
+    }
+}
+
+/// Verifies that a reference clip (e.g. a preview) is part of a candidate track
+///
+/// Both signals are resampled to a common rate and reduced to amplitude envelopes,
+/// which are cross correlated to find the offset of the reference within the candidate.
+/// The normalized correlation makes scores comparable across tracks.
+#[must_use]
+pub fn verify(reference: &Samples, candidate: &Samples, options: &VerifyOptions) -> Verification {
+    // envelope blocks only span the same duration at the same sample rate
+    let sample_rate = reference.sample_rate.min(candidate.sample_rate);
+    let reference = reference.resample(sample_rate);
+    let candidate = candidate.resample(sample_rate);
+
+    let block = reference.envelope_block(options.envelope_rate);
+    let reference = reference.envelope(options.envelope_rate);
+    let candidate = candidate.envelope(options.envelope_rate);
+
+    if reference.is_empty() || candidate.len() < reference.len() {
+        return Verification::default();
+    }
+
+    match coarse_to_fine(&candidate, &reference, &options.search) {
+        Some(peak) => Verification {
+            offset: Duration::from_secs_f64((peak.lag * block) as f64 / f64::from(sample_rate)),
+            confidence: peak.value.clamp(0.0, 1.0),
+            peak_to_sidelobe: peak.peak_to_sidelobe,
+            peak_to_median: peak.peak_to_median,
+        },
+        None => Verification::default(),
+    }
+}
+
+/// Decodes and verifies that a reference clip is part of a candidate file
+///
+/// # Errors
+/// If either of the files cannot be decoded.
+pub fn verify_files(
+    reference: impl AsRef<Path>,
+    candidate: impl AsRef<Path>,
+    options: &VerifyOptions,
+) -> Result<Verification> {
+    let reference = Samples::from_file(reference)?;
+    let candidate = Samples::from_file(candidate)?;
+    Ok(verify(&reference, &candidate, options))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{verify, verify_files, VerifyOptions};
+    use crate::decode::Samples;
+    use approx::assert_abs_diff_eq;
+    use ndarray::prelude::*;
+    use std::path::Path;
+
+    pub(crate) fn noise(len: usize, seed: u64) -> Array1<f32> {
+        let mut state = seed;
+        Array::from_iter((0..len).map(|_| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            ((state >> 33) as f32 / (1u64 << 31) as f32) - 1.0
+        }))
+    }
+
+    /// Noise with a slowly varying random amplitude
+    fn modulated_noise(len: usize, block: usize, seed: u64) -> Array1<f32> {
+        let amplitude = noise(len / block + 1, seed + 1);
+        noise(len, seed) * Array::from_iter((0..len).map(|i| amplitude[i / block].abs()))
+    }
+
+    #[test]
+    fn test_verify_finds_clip() {
+        let sample_rate = 8_000;
+        let track = modulated_noise(sample_rate * 30, 800, 1);
+        let track = Samples::new(track, sample_rate as u32);
+        let clip = Samples::new(
+            track
+                .data
+                .slice(s![sample_rate * 12..sample_rate * 20])
+                .to_owned(),
+            sample_rate as u32,
+        );
+        let options = VerifyOptions::default();
+        let verification = verify(&clip, &track, &options);
+        assert_abs_diff_eq!(verification.offset.as_secs_f32(), 12.0, epsilon = 0.01);
+        assert!(verification.is_match(&options));
+    }
+
+    #[test]
+    fn test_verify_rejects_unrelated() {
+        let sample_rate = 8_000;
+        let track = Samples::new(
+            modulated_noise(sample_rate * 30, 800, 1),
+            sample_rate as u32,
+        );
+        let clip = Samples::new(modulated_noise(sample_rate * 8, 800, 5), sample_rate as u32);
+        let options = VerifyOptions::default();
+        let verification = verify(&clip, &track, &options);
+        assert!(!verification.is_match(&options));
+    }
+
+    #[test]
+    fn test_verify_mismatched_sample_rates() {
+        let sample_rate = 48_000;
+        let track = Samples::new(
+            modulated_noise(sample_rate * 20, 4_800, 1),
+            sample_rate as u32,
+        );
+        // e.g. a 44.1 kHz preview of a 48 kHz download
+        let clip = Samples::new(
+            track
+                .data
+                .slice(s![sample_rate * 12..sample_rate * 18])
+                .to_owned(),
+            sample_rate as u32,
+        )
+        .resample(44_100);
+        let options = VerifyOptions::default();
+        let verification = verify(&clip, &track, &options);
+        assert_abs_diff_eq!(verification.offset.as_secs_f32(), 12.0, epsilon = 0.01);
+        assert!(verification.is_match(&options));
+    }
+
+    #[test]
+    fn test_verify_preview_file() {
+        let samples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../experimental/audio-samples");
+        let options = VerifyOptions::default();
+        let verification = verify_files(
+            samples.join("muse_preview.mp3"),
+            samples.join("muse_uprising.mp3"),
+            &options,
+        )
+        .unwrap();
+        assert_abs_diff_eq!(verification.offset.as_secs_f32(), 147.0, epsilon = 1.0);
+        assert!(verification.is_match(&options));
+    }
+}
@@ -0,0 +1,19 @@
+use super::ConfigError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("io error reading or writing library: {0}")]
+    IO(#[from] std::io::Error),
+    #[error("failed to parse library entry: {0}")]
+    ParseError(#[from] serde_json::Error),
+    #[error("failed to read or write tag: {0}")]
+    Tag(#[from] id3::Error),
+    #[error("track link has no source track ID")]
+    MissingLinkSource,
+    #[error("unknown service `{0}`")]
+    UnknownService(i32),
+    #[error("playlist snapshot has no playlist ID")]
+    MissingPlaylistId,
+}
@@ -1,10 +1,20 @@
 mod config;
+mod error;
 mod persist;
+pub mod tags;
 pub use config::{Config, ConfigError};
+pub use error::Error;
 pub use persist::Persist;
 
+use djtool_model::{PlaylistId, PlaylistSnapshot, Service, TrackAnalysis, TrackId, TrackLink};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+const CACHE_DIR: &str = ".djtool";
+const WAVEFORM_DIR: &str = "waveforms";
+const ANALYSIS_DIR: &str = "analysis";
+const LINK_DIR: &str = "links";
+const PLAYLIST_DIR: &str = "playlists";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Library {
@@ -22,3 +32,297 @@ impl Default for Library {
 }
 
 impl Persist for Library {}
+
+/// File name safe form of an id
+///
+/// The id is hex encoded, such that distinct ids never collide,
+/// not even ids that only differ in case on case insensitive file systems.
+fn cache_key(id: &str) -> String {
+    id.bytes().map(|b| format!("{b:02x}")).collect()
+}
+
+async fn read_cached(path: impl AsRef<Path>) -> Result<Option<Vec<u8>>, Error> {
+    match tokio::fs::read(path).await {
+        Ok(data) => Ok(Some(data)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn write_cached(path: impl AsRef<Path>, data: &[u8]) -> Result<(), Error> {
+    if let Some(dir) = path.as_ref().parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(path, data).await?;
+    Ok(())
+}
+
+impl Library {
+    /// Directory for derived data such as waveforms
+    pub fn cache_dir(&self) -> PathBuf {
+        self.path.join(CACHE_DIR)
+    }
+
+    /// Path of the cached waveform of a track
+    pub fn waveform_path(&self, id: &TrackId) -> PathBuf {
+        self.cache_dir()
+            .join(WAVEFORM_DIR)
+            .join(format!("{}.waveform", cache_key(&id.to_string())))
+    }
+
+    /// Loads the cached waveform blob of a track, if any
+    pub async fn load_waveform(&self, id: &TrackId) -> Result<Option<Vec<u8>>, Error> {
+        read_cached(self.waveform_path(id)).await
+    }
+
+    /// Caches the waveform blob of a track
+    pub async fn save_waveform(&self, id: &TrackId, waveform: &[u8]) -> Result<(), Error> {
+        write_cached(self.waveform_path(id), waveform).await
+    }
+
+    /// Path of the stored analysis of a track, e.g. its tempo, key and cue points
+    pub fn analysis_path(&self, id: &TrackId) -> PathBuf {
+        self.cache_dir()
+            .join(ANALYSIS_DIR)
+            .join(format!("{}.json", cache_key(&id.to_string())))
+    }
+
+    /// Loads the stored analysis of a track, if any
+    pub async fn load_analysis(&self, id: &TrackId) -> Result<Option<TrackAnalysis>, Error> {
+        match read_cached(self.analysis_path(id)).await? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores the analysis of a track with its library entry
+    pub async fn save_analysis(&self, id: &TrackId, analysis: &TrackAnalysis) -> Result<(), Error> {
+        let data = serde_json::to_vec(analysis)?;
+        write_cached(self.analysis_path(id), &data).await
+    }
+
+    /// Path of the stored link of a source track to its sink track
+    ///
+    /// Fails for tracks of an unknown service, whose links could not be told apart.
+    pub fn link_path(&self, source_id: &TrackId) -> Result<PathBuf, Error> {
+        if Service::from_i32(source_id.source).is_none() {
+            return Err(Error::UnknownService(source_id.source));
+        }
+        Ok(self
+            .cache_dir()
+            .join(LINK_DIR)
+            .join(format!("{}.json", cache_key(&source_id.to_string()))))
+    }
+
+    /// Loads the previous decision for a source track, if any
+    pub async fn load_link(&self, source_id: &TrackId) -> Result<Option<TrackLink>, Error> {
+        match read_cached(self.link_path(source_id)?).await? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores the decision for a source track
+    ///
+    /// Returns `false` without storing the link if it would replace a manual override.
+    pub async fn save_link(&self, link: &TrackLink) -> Result<bool, Error> {
+        let source_id = link.source_id.as_ref().ok_or(Error::MissingLinkSource)?;
+        if let Some(previous) = self.load_link(source_id).await? {
+            if !link.replaces(&previous) {
+                return Ok(false);
+            }
+        }
+        let data = serde_json::to_vec(link)?;
+        write_cached(self.link_path(source_id)?, &data).await?;
+        Ok(true)
+    }
+
+    /// Path of the snapshot of a playlist as of its last sync
+    pub fn playlist_snapshot_path(&self, id: &PlaylistId) -> PathBuf {
+        self.cache_dir()
+            .join(PLAYLIST_DIR)
+            .join(format!("{}.json", cache_key(&id.to_string())))
+    }
+
+    /// Loads the snapshot of a playlist, if it was synced before
+    pub async fn load_playlist_snapshot(
+        &self,
+        id: &PlaylistId,
+    ) -> Result<Option<PlaylistSnapshot>, Error> {
+        match read_cached(self.playlist_snapshot_path(id)).await? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores the snapshot of a playlist once it was synced
+    pub async fn save_playlist_snapshot(&self, snapshot: &PlaylistSnapshot) -> Result<(), Error> {
+        let id = snapshot.id.as_ref().ok_or(Error::MissingPlaylistId)?;
+        let data = serde_json::to_vec(snapshot)?;
+        write_cached(self.playlist_snapshot_path(id), &data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cache_key, Error, Library};
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(
+            cache_key("SPOTIFY:TRACK:aB-"),
+            "53504f544946593a545241434b3a61422d"
+        );
+        // base62 spotify ids may only differ in case
+        assert_ne!(
+            cache_key("SPOTIFY:TRACK:4uLU6hMCjMI75M1A2tKUQC").to_lowercase(),
+            cache_key("SPOTIFY:TRACK:4ulu6hmcjmi75m1a2tkuqc").to_lowercase()
+        );
+        assert_ne!(cache_key("SPOTIFY:TRACK:a"), cache_key("SPOTIFY_TRACK_a"));
+    }
+
+    #[tokio::test]
+    async fn test_waveform_cache() {
+        use djtool_model::{Service, TrackId};
+
+        let dir = tempfile::tempdir().unwrap();
+        let library = Library {
+            path: dir.path().to_owned(),
+        };
+        let id = TrackId {
+            id: "4uLU6hMCjMI75M1A2tKUQC".to_string(),
+            source: Service::Spotify as i32,
+            playlist_id: None,
+        };
+        assert!(library.waveform_path(&id).ends_with(format!(
+            ".djtool/waveforms/{}.waveform",
+            cache_key(&id.to_string())
+        )));
+        assert_eq!(library.load_waveform(&id).await.unwrap(), None);
+        library.save_waveform(&id, &[1, 2, 3]).await.unwrap();
+        assert_eq!(
+            library.load_waveform(&id).await.unwrap(),
+            Some(vec![1, 2, 3])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_analysis_store() {
+        use djtool_model::{
+            BeatGrid, CueKind, CuePoint, KeyMode, MusicalKey, Service, Tempo, TrackAnalysis,
+            TrackId,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let library = Library {
+            path: dir.path().to_owned(),
+        };
+        let id = TrackId {
+            id: "4uLU6hMCjMI75M1A2tKUQC".to_string(),
+            source: Service::Spotify as i32,
+            playlist_id: None,
+        };
+        let cue = |kind: CueKind, position_secs: f64| CuePoint {
+            kind: kind as i32,
+            position_secs,
+        };
+        let analysis = TrackAnalysis {
+            tempo: Some(Tempo {
+                bpm: 128.0,
+                confidence: 0.9,
+            }),
+            key: Some(MusicalKey {
+                tonic: 9,
+                mode: KeyMode::Minor as i32,
+                confidence: 0.7,
+            }),
+            beat_grid: Some(BeatGrid {
+                anchor_secs: 0.25,
+                bpm: 128.0,
+                beats_per_bar: 4,
+                downbeat: Some(1),
+                tempo_changes: vec![],
+            }),
+            cues: vec![
+                cue(CueKind::IntroEnd, 30.25),
+                cue(CueKind::FirstDrop, 60.25),
+                cue(CueKind::OutroStart, 180.25),
+            ],
+        };
+        assert_eq!(library.load_analysis(&id).await.unwrap(), None);
+        library.save_analysis(&id, &analysis).await.unwrap();
+        assert_eq!(library.load_analysis(&id).await.unwrap(), Some(analysis));
+    }
+
+    #[tokio::test]
+    async fn test_link_store() {
+        use djtool_model::{MatchMethod, Service, TrackId, TrackLink};
+
+        let dir = tempfile::tempdir().unwrap();
+        let library = Library {
+            path: dir.path().to_owned(),
+        };
+        let track_id = |id: &str, source: Service| TrackId {
+            id: id.to_string(),
+            source: source as i32,
+            playlist_id: None,
+        };
+        let source_id = track_id("4uLU6hMCjMI75M1A2tKUQC", Service::Spotify);
+        assert_eq!(library.load_link(&source_id).await.unwrap(), None);
+
+        let mut manual = TrackLink::new(
+            source_id.clone(),
+            track_id("dQw4w9WgXcQ", Service::Youtube),
+            1.0,
+            MatchMethod::First,
+        );
+        manual.manual_override = true;
+        assert!(library.save_link(&manual).await.unwrap());
+
+        // automatic matching does not replace the manual decision
+        let automatic = TrackLink::new(
+            source_id.clone(),
+            track_id("other", Service::Youtube),
+            0.9,
+            MatchMethod::Best,
+        );
+        assert!(!library.save_link(&automatic).await.unwrap());
+        assert_eq!(library.load_link(&source_id).await.unwrap(), Some(manual));
+
+        let unknown = TrackId {
+            id: "1".to_string(),
+            source: 42,
+            playlist_id: None,
+        };
+        assert!(matches!(
+            library.load_link(&unknown).await,
+            Err(Error::UnknownService(42))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_playlist_snapshot_store() {
+        use djtool_model::{PlaylistId, PlaylistSnapshot, Service};
+
+        let dir = tempfile::tempdir().unwrap();
+        let library = Library {
+            path: dir.path().to_owned(),
+        };
+        let id = PlaylistId {
+            id: "37i9dQZF1DXcBWIGoYBM5M".to_string(),
+            source: Service::Spotify as i32,
+        };
+        assert!(library.playlist_snapshot_path(&id).ends_with(format!(
+            ".djtool/playlists/{}.json",
+            cache_key(&id.to_string())
+        )));
+        assert_eq!(library.load_playlist_snapshot(&id).await.unwrap(), None);
+
+        let snapshot = PlaylistSnapshot::new(id.clone(), "MTY4".to_string(), &[]);
+        library.save_playlist_snapshot(&snapshot).await.unwrap();
+        assert_eq!(
+            library.load_playlist_snapshot(&id).await.unwrap(),
+            Some(snapshot)
+        );
+    }
+}
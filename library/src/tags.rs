@@ -0,0 +1,83 @@
+use super::Error;
+use id3::TagLike;
+use std::path::Path;
+
+/// Reads the id3 tag of a file, or starts a new tag if it has none
+///
+/// Corrupted tags are recovered partially where possible.
+fn read_tag(path: &Path) -> Result<id3::Tag, Error> {
+    match id3::Tag::read_from_path(path) {
+        Ok(tag) => Ok(tag),
+        Err(err) if matches!(err.kind, id3::ErrorKind::NoTag) => Ok(id3::Tag::new()),
+        Err(mut err) => match err.partial_tag.take() {
+            Some(tag) => Ok(tag),
+            None => Err(err.into()),
+        },
+    }
+}
+
+/// Writes the tempo in beats per minute (TBPM)
+pub fn write_bpm(path: impl AsRef<Path>, bpm: u32) -> Result<(), Error> {
+    let mut tag = read_tag(path.as_ref())?;
+    tag.set_text("TBPM", bpm.to_string());
+    tag.write_to_path(path, id3::Version::Id3v23)?;
+    Ok(())
+}
+
+/// Writes the ReplayGain track gain in dB and peak as a linear amplitude
+pub fn write_replaygain(path: impl AsRef<Path>, gain: f64, peak: f64) -> Result<(), Error> {
+    let mut tag = read_tag(path.as_ref())?;
+    tag.add_frame(id3::frame::ExtendedText {
+        description: "REPLAYGAIN_TRACK_GAIN".to_string(),
+        value: format!("{gain:.2} dB"),
+    });
+    tag.add_frame(id3::frame::ExtendedText {
+        description: "REPLAYGAIN_TRACK_PEAK".to_string(),
+        value: format!("{peak:.6}"),
+    });
+    tag.write_to_path(path, id3::Version::Id3v23)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use id3::TagLike;
+
+    #[test]
+    fn test_write_bpm() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("track.mp3");
+        std::fs::write(&path, [0u8; 128]).unwrap();
+
+        super::write_bpm(&path, 124).unwrap();
+        super::write_bpm(&path, 128).unwrap();
+        let tag = id3::Tag::read_from_path(&path).unwrap();
+        assert_eq!(
+            tag.get("TBPM").and_then(|frame| frame.content().text()),
+            Some("128")
+        );
+    }
+
+    #[test]
+    fn test_write_replaygain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("track.mp3");
+        std::fs::write(&path, [0u8; 128]).unwrap();
+
+        super::write_replaygain(&path, -3.0, 1.0).unwrap();
+        super::write_replaygain(&path, -7.514, 0.988_123_4).unwrap();
+        let tag = id3::Tag::read_from_path(&path).unwrap();
+        let mut replaygain: Vec<(&str, &str)> = tag
+            .extended_texts()
+            .map(|text| (text.description.as_str(), text.value.as_str()))
+            .collect();
+        replaygain.sort();
+        assert_eq!(
+            replaygain,
+            vec![
+                ("REPLAYGAIN_TRACK_GAIN", "-7.51 dB"),
+                ("REPLAYGAIN_TRACK_PEAK", "0.988123"),
+            ]
+        );
+    }
+}
@@ -1,4 +1,89 @@
-use std::path::PathBuf;
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{DescriptorProto, FileDescriptorSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const PACKAGE: &str = "proto.djtool";
+
+/// Fields whose proto3 JSON mapping is not the serde default,
+/// collected from the descriptors of the proto files
+#[derive(Default)]
+struct JsonFields {
+    oneofs: Vec<String>,
+    /// enum fields and the snake case name of their enum
+    enums: Vec<(String, String)>,
+    uint64s: Vec<String>,
+}
+
+impl JsonFields {
+    fn collect(&mut self, parent: &str, message: &DescriptorProto) {
+        let path = format!("{parent}.{}", message.name());
+        for field in &message.field {
+            let field_path = format!("{path}.{}", field.name());
+            let repeated = field.label() == Label::Repeated;
+            match field.r#type() {
+                Type::Enum => {
+                    assert!(!repeated, "repeated enum {field_path} is not supported");
+                    let name = field
+                        .type_name()
+                        .strip_prefix(&format!(".{PACKAGE}."))
+                        .filter(|name| !name.contains('.'))
+                        .unwrap_or_else(|| panic!("enum of {field_path} is not top level"));
+                    self.enums.push((field_path, snake_case(name)));
+                }
+                Type::Uint64 => {
+                    assert!(!repeated, "repeated uint64 {field_path} is not supported");
+                    self.uint64s.push(field_path);
+                }
+                Type::Int64 | Type::Sint64 | Type::Fixed64 | Type::Sfixed64 => {
+                    panic!("64 bit integer {field_path} has no JSON mapping")
+                }
+                _ => {}
+            }
+        }
+        // synthetic oneofs of proto3 optional fields are plain options
+        for (index, oneof) in message.oneof_decl.iter().enumerate() {
+            let synthetic = message
+                .field
+                .iter()
+                .any(|field| field.proto3_optional() && field.oneof_index == Some(index as i32));
+            if !synthetic {
+                self.oneofs.push(format!("{path}.{}", oneof.name()));
+            }
+        }
+        for nested in &message.nested_type {
+            self.collect(&path, nested);
+        }
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+fn descriptor_set(protos: &[PathBuf], proto_dir: &Path, path: &Path) -> FileDescriptorSet {
+    let mut cmd = Command::new(prost_build::protoc_from_env());
+    cmd.arg("--include_imports")
+        .arg("--include_source_info")
+        .arg("-o")
+        .arg(path)
+        .arg("-I")
+        .arg(proto_dir);
+    if let Some(include) = prost_build::protoc_include_from_env() {
+        cmd.arg("-I").arg(include);
+    }
+    let status = cmd.args(protos).status().unwrap();
+    assert!(status.success(), "protoc failed: {status}");
+    FileDescriptorSet::decode(std::fs::read(path).unwrap().as_slice()).unwrap()
+}
 
 fn main() {
     let source_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -10,22 +95,69 @@ fn main() {
     std::fs::create_dir_all(&output_dir).ok();
 
     println!("cargo:rerun-if-changed=proto/djtool.proto");
-    let builder = tonic_build::configure()
-        .type_attribute(
-            "proto.djtool.TrackId",
-            "#[derive(serde::Serialize, serde::Deserialize, Hash, Eq)]",
-        )
-        .type_attribute(
-            "proto.djtool.PlaylistId",
-            "#[derive(serde::Serialize, serde::Deserialize, Hash, Eq)]",
+    let protos = [
+        proto_dir.join("model.proto"),
+        proto_dir.join("service.proto"),
+    ];
+    let descriptor_path = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("djtool.bin");
+    let descriptors = descriptor_set(&protos, &proto_dir, &descriptor_path);
+
+    let mut fields = JsonFields::default();
+    let mut enumerations = String::new();
+    for file in descriptors
+        .file
+        .iter()
+        .filter(|file| file.package() == PACKAGE)
+    {
+        for message in &file.message_type {
+            fields.collect(PACKAGE, message);
+        }
+        for enumeration in &file.enum_type {
+            let name = enumeration.name();
+            enumerations += &format!("enumeration!({}, {name});\n", snake_case(name));
+        }
+    }
+    std::fs::write(output_dir.join("enumerations.rs"), enumerations).unwrap();
+
+    // only the messages of the service are generated until the server is enabled
+    let mut builder = tonic_build::configure()
+        .build_server(false)
+        .build_client(false)
+        // canonical proto3 JSON mapping
+        .message_attribute(
+            ".",
+            "#[derive(serde::Serialize, serde::Deserialize)]\n#[serde(default, rename_all = \"camelCase\")]",
         )
-        .type_attribute(
-            "proto.djtool.UserId",
-            "#[derive(serde::Serialize, serde::Deserialize, Hash, Eq)]",
-        );
+        .type_attribute("proto.djtool.TrackId", "#[derive(Hash, Eq)]")
+        .type_attribute("proto.djtool.PlaylistId", "#[derive(Hash, Eq)]")
+        .type_attribute("proto.djtool.UserId", "#[derive(Hash, Eq)]")
+        .type_attribute("proto.djtool.AlbumId", "#[derive(Hash, Eq)]")
+        .type_attribute("proto.djtool.ArtistId", "#[derive(Hash, Eq)]");
+
+    // oneofs are flattened into their message
+    for oneof in &fields.oneofs {
+        builder = builder
+            .enum_attribute(
+                oneof,
+                "#[derive(serde::Serialize, serde::Deserialize)]\n#[serde(rename_all = \"camelCase\")]",
+            )
+            .field_attribute(oneof, "#[serde(flatten)]");
+    }
+    // enums are written as their names
+    for (field, module) in &fields.enums {
+        builder =
+            builder.field_attribute(field, format!("#[serde(with = \"crate::json::{module}\")]"));
+    }
+    // 64 bit integers are written as strings
+    for field in &fields.uint64s {
+        builder = builder.field_attribute(field, "#[serde(with = \"crate::json::uint64\")]");
+    }
+
     builder
         .out_dir(&output_dir)
-        .compile(&[proto_dir.join("model.proto")], &[&proto_dir])
+        .file_descriptor_set_path(&descriptor_path)
+        .skip_protoc_run()
+        .compile(&protos, &[&proto_dir])
         .unwrap();
 
     // tonic_build::configure()
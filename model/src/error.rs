@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+/// Delay before the first retry of a transient error, doubled with every attempt
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Number of retries before rate limited and transient requests give up
+pub const MAX_ATTEMPTS: u32 = 8;
+
+/// Errors of sources and sinks
+///
+/// Every service maps its errors into these, such that callers
+/// can decide whether to retry, ask the user to login or give up.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("unauthorized")]
+    Unauthorized,
+    #[error("user must login at: {auth_url}")]
+    RequiresLogin { auth_url: url::Url },
+    #[error("rate limited (retry after {retry_after:?})")]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("not found")]
+    NotFound,
+    #[error("unavailable in region {region:?}")]
+    Unavailable { region: Option<String> },
+    #[error("transient network error: {0}")]
+    Transient(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("failed to parse response: {0}")]
+    Parse(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("{0}")]
+    Custom(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// Whether the given retry attempt, starting at zero, may succeed without user interaction
+    ///
+    /// Unauthorized requests are not retried, since nothing refreshes the token in between.
+    pub fn is_retryable(&self, attempt: u32) -> bool {
+        self.retry_after(attempt).is_some()
+    }
+
+    /// Delay before the given retry attempt, starting at zero, or `None` to give up
+    pub fn retry_after(&self, attempt: u32) -> Option<Duration> {
+        if attempt >= MAX_ATTEMPTS {
+            return None;
+        }
+        let backoff = INITIAL_BACKOFF
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF);
+        match self {
+            Self::RateLimited { retry_after } => Some(retry_after.unwrap_or(backoff)),
+            Self::Transient(_) => Some(backoff),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, MAX_ATTEMPTS};
+    use std::time::Duration;
+
+    #[test]
+    fn test_retry_after() {
+        let rate_limited = Error::RateLimited {
+            retry_after: Some(Duration::from_secs(7)),
+        };
+        assert!(rate_limited.is_retryable(0));
+        assert_eq!(rate_limited.retry_after(3), Some(Duration::from_secs(7)));
+        assert_eq!(rate_limited.retry_after(MAX_ATTEMPTS), None);
+        assert!(!rate_limited.is_retryable(MAX_ATTEMPTS));
+
+        let transient = Error::Transient("connection reset".into());
+        assert_eq!(transient.retry_after(0), Some(Duration::from_secs(1)));
+        assert_eq!(transient.retry_after(2), Some(Duration::from_secs(4)));
+        assert_eq!(
+            transient.retry_after(MAX_ATTEMPTS - 1),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(transient.retry_after(MAX_ATTEMPTS), None);
+
+        assert_eq!(Error::Unauthorized.retry_after(0), None);
+        assert!(!Error::Unauthorized.is_retryable(0));
+        assert!(!Error::NotFound.is_retryable(0));
+        assert_eq!(Error::NotFound.retry_after(0), None);
+    }
+}
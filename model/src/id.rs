@@ -0,0 +1,356 @@
+use super::{AlbumId, ArtistId, PlaylistId, Service, TrackId, UserId};
+use std::str::FromStr;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseIdError {
+    #[error("unknown service `{0}`")]
+    UnknownService(String),
+    #[error("unrecognized id, url or uri `{0}`")]
+    Invalid(String),
+    #[error("expected a {expected} but `{input}` is a {found}")]
+    WrongKind {
+        input: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+/// Any resource of a service that can be referred to by an ID, URL or URI
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Track(TrackId),
+    Playlist(PlaylistId),
+    User(UserId),
+    Album(AlbumId),
+    Artist(ArtistId),
+}
+
+impl Resource {
+    pub fn service(&self) -> Service {
+        let source = match self {
+            Self::Track(id) => id.source,
+            Self::Playlist(id) => id.source,
+            Self::User(id) => id.source,
+            Self::Album(id) => id.source,
+            Self::Artist(id) => id.source,
+        };
+        Service::from_i32(source).unwrap_or_default()
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Track(_) => "track",
+            Self::Playlist(_) => "playlist",
+            Self::User(_) => "user",
+            Self::Album(_) => "album",
+            Self::Artist(_) => "artist",
+        }
+    }
+
+    fn new(service: Service, kind: &str, id: impl Into<String>) -> Option<Self> {
+        let source = service as i32;
+        let id = id.into();
+        if id.is_empty() {
+            return None;
+        }
+        match kind.to_ascii_lowercase().as_str() {
+            "track" => Some(Self::Track(TrackId {
+                id,
+                source,
+                playlist_id: None,
+            })),
+            "playlist" => Some(Self::Playlist(PlaylistId { id, source })),
+            "user" => Some(Self::User(UserId { id, source })),
+            "album" => Some(Self::Album(AlbumId { id, source })),
+            "artist" => Some(Self::Artist(ArtistId { id, source })),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Service {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Service::from_str_name(&s.trim().to_ascii_uppercase())
+            .ok_or_else(|| ParseIdError::UnknownService(s.to_string()))
+    }
+}
+
+/// `SERVICE:KIND:id` as written by the `Display` implementations
+fn parse_canonical(input: &str) -> Option<Resource> {
+    let mut parts = input.splitn(3, ':');
+    let service = Service::from_str_name(parts.next()?)?;
+    let kind = parts.next()?;
+    if kind != kind.to_ascii_uppercase() {
+        return None;
+    }
+    Resource::new(service, kind, parts.next()?)
+}
+
+/// `spotify:track:id` and the legacy `spotify:user:name:playlist:id`
+fn parse_spotify_uri(input: &str) -> Option<Resource> {
+    let parts: Vec<&str> = input.split(':').collect();
+    match parts.as_slice() {
+        ["spotify", "user", _, "playlist", id] => Resource::new(Service::Spotify, "playlist", *id),
+        ["spotify", kind, id] => Resource::new(Service::Spotify, kind, *id),
+        _ => None,
+    }
+}
+
+fn parse_url(input: &str) -> Option<Resource> {
+    let url = if input.contains("://") {
+        url::Url::parse(input).ok()?
+    } else {
+        url::Url::parse(&format!("https://{input}")).ok()?
+    };
+    let host = url.host_str()?.trim_start_matches("www.");
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    };
+
+    match host {
+        "open.spotify.com" | "play.spotify.com" => {
+            // skip locale prefixes such as `intl-de`
+            let segments: Vec<&str> = segments
+                .into_iter()
+                .skip_while(|s| s.starts_with("intl-"))
+                .collect();
+            match segments.as_slice() {
+                ["user", _, "playlist", id, ..] => Resource::new(Service::Spotify, "playlist", *id),
+                [kind, id, ..] => Resource::new(Service::Spotify, kind, *id),
+                _ => None,
+            }
+        }
+        "youtu.be" => Resource::new(Service::Youtube, "track", *segments.first()?),
+        "youtube.com" | "m.youtube.com" | "music.youtube.com" => match segments.as_slice() {
+            ["watch"] => Resource::new(Service::Youtube, "track", query("v")?),
+            ["shorts", id] | ["embed", id] | ["live", id] => {
+                Resource::new(Service::Youtube, "track", *id)
+            }
+            ["playlist"] => Resource::new(Service::Youtube, "playlist", query("list")?),
+            ["channel", id] => Resource::new(Service::Youtube, "user", *id),
+            [handle] if handle.starts_with('@') => Resource::new(Service::Youtube, "user", *handle),
+            _ => None,
+        },
+        // soundcloud resources are identified by their permalink
+        "soundcloud.com" | "m.soundcloud.com" => match segments.as_slice() {
+            [user] => Resource::new(Service::Soundcloud, "user", *user),
+            [user, "sets", set] => Resource::new(
+                Service::Soundcloud,
+                "playlist",
+                format!("{user}/sets/{set}"),
+            ),
+            [user, track] => Resource::new(Service::Soundcloud, "track", format!("{user}/{track}")),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Resolves an ID, URL or URI of any supported service
+///
+/// Accepts the `SERVICE:KIND:id` format of the `Display` implementations,
+/// Spotify URIs such as `spotify:track:id`, and links to Spotify, YouTube and SoundCloud.
+pub fn resolve(input: &str) -> Result<Resource, ParseIdError> {
+    let trimmed = input.trim();
+    parse_canonical(trimmed)
+        .or_else(|| parse_spotify_uri(trimmed))
+        .or_else(|| parse_url(trimmed))
+        .ok_or_else(|| ParseIdError::Invalid(input.to_string()))
+}
+
+fn wrong_kind(input: &str, expected: &'static str, found: &Resource) -> ParseIdError {
+    ParseIdError::WrongKind {
+        input: input.to_string(),
+        expected,
+        found: found.kind(),
+    }
+}
+
+impl FromStr for TrackId {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match resolve(s)? {
+            Resource::Track(id) => Ok(id),
+            other => Err(wrong_kind(s, "track", &other)),
+        }
+    }
+}
+
+impl FromStr for PlaylistId {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match resolve(s)? {
+            Resource::Playlist(id) => Ok(id),
+            other => Err(wrong_kind(s, "playlist", &other)),
+        }
+    }
+}
+
+impl FromStr for UserId {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match resolve(s)? {
+            Resource::User(id) => Ok(id),
+            other => Err(wrong_kind(s, "user", &other)),
+        }
+    }
+}
+
+impl FromStr for AlbumId {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match resolve(s)? {
+            Resource::Album(id) => Ok(id),
+            other => Err(wrong_kind(s, "album", &other)),
+        }
+    }
+}
+
+impl FromStr for ArtistId {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match resolve(s)? {
+            Resource::Artist(id) => Ok(id),
+            other => Err(wrong_kind(s, "artist", &other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve, ParseIdError, Resource};
+    use crate::{AlbumId, ArtistId, PlaylistId, Service, TrackId, UserId};
+
+    fn track(service: Service, id: &str) -> Resource {
+        Resource::Track(TrackId {
+            id: id.to_string(),
+            source: service as i32,
+            playlist_id: None,
+        })
+    }
+
+    fn playlist(service: Service, id: &str) -> Resource {
+        Resource::Playlist(PlaylistId {
+            id: id.to_string(),
+            source: service as i32,
+        })
+    }
+
+    #[test]
+    fn test_resolve() {
+        let cases = [
+            (
+                "https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC?si=abc",
+                track(Service::Spotify, "4uLU6hMCjMI75M1A2tKUQC"),
+            ),
+            (
+                "open.spotify.com/intl-de/playlist/37i9dQZF1DXcBWIGoYBM5M",
+                playlist(Service::Spotify, "37i9dQZF1DXcBWIGoYBM5M"),
+            ),
+            (
+                "spotify:track:4uLU6hMCjMI75M1A2tKUQC",
+                track(Service::Spotify, "4uLU6hMCjMI75M1A2tKUQC"),
+            ),
+            (
+                "spotify:user:someone:playlist:37i9dQZF1DXcBWIGoYBM5M",
+                playlist(Service::Spotify, "37i9dQZF1DXcBWIGoYBM5M"),
+            ),
+            (
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PL123",
+                track(Service::Youtube, "dQw4w9WgXcQ"),
+            ),
+            (
+                "https://youtu.be/dQw4w9WgXcQ?t=42",
+                track(Service::Youtube, "dQw4w9WgXcQ"),
+            ),
+            (
+                "music.youtube.com/playlist?list=PL123",
+                playlist(Service::Youtube, "PL123"),
+            ),
+            (
+                "https://soundcloud.com/artist/some-track",
+                track(Service::Soundcloud, "artist/some-track"),
+            ),
+            (
+                "https://soundcloud.com/artist/sets/some-set",
+                playlist(Service::Soundcloud, "artist/sets/some-set"),
+            ),
+            (
+                "https://soundcloud.com/artist",
+                Resource::User(UserId {
+                    id: "artist".to_string(),
+                    source: Service::Soundcloud as i32,
+                }),
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(resolve(input), Ok(expected), "{input}");
+        }
+        assert!(matches!(
+            resolve("https://example.com/track/1"),
+            Err(ParseIdError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let id: TrackId = "https://youtu.be/dQw4w9WgXcQ".parse().unwrap();
+        assert_eq!(id.to_string(), "YOUTUBE:TRACK:dQw4w9WgXcQ");
+        assert_eq!(id.to_string().parse::<TrackId>(), Ok(id));
+
+        let id: PlaylistId = "spotify:playlist:37i9dQZF1DXcBWIGoYBM5M".parse().unwrap();
+        assert_eq!(id.to_string().parse::<PlaylistId>(), Ok(id));
+        assert_eq!(
+            resolve("SPOTIFY:PLAYLIST:37i9dQZF1DXcBWIGoYBM5M")
+                .unwrap()
+                .service(),
+            Service::Spotify
+        );
+
+        assert!(matches!(
+            "spotify:track:4uLU6hMCjMI75M1A2tKUQC".parse::<PlaylistId>(),
+            Err(ParseIdError::WrongKind { .. })
+        ));
+        assert_eq!("youtube".parse::<Service>(), Ok(Service::Youtube));
+
+        let id: AlbumId = "https://open.spotify.com/album/4aawyAB9vmqN3uQ7FjRGTy?si=x"
+            .parse()
+            .unwrap();
+        assert_eq!(id.to_string(), "SPOTIFY:ALBUM:4aawyAB9vmqN3uQ7FjRGTy");
+        assert_eq!(id.to_string().parse::<AlbumId>(), Ok(id));
+        let id: ArtistId = "spotify:artist:0TnOYISbd1XYRBk9myaseg".parse().unwrap();
+        assert_eq!(id.to_string().parse::<ArtistId>(), Ok(id));
+    }
+
+    #[test]
+    fn test_display_unknown_service() {
+        let id = TrackId {
+            id: "1".to_string(),
+            source: 42,
+            playlist_id: None,
+        };
+        assert_eq!(id.to_string(), "UNKNOWN:TRACK:1");
+        assert_eq!(
+            id.to_string().parse::<TrackId>(),
+            Err(ParseIdError::Invalid("UNKNOWN:TRACK:1".to_string()))
+        );
+        let id = UserId {
+            id: "1".to_string(),
+            source: 42,
+        };
+        assert_eq!(id.to_string(), "UNKNOWN:USER:1");
+    }
+}
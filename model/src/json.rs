@@ -0,0 +1,197 @@
+//! Helpers for the proto3 JSON mapping of the generated types
+//!
+//! Enums are written as their names and 64 bit integers as strings,
+//! but both are also accepted as plain numbers when reading.
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serializer};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NameOrNumber<T> {
+    Number(T),
+    Name(String),
+}
+
+macro_rules! enumeration {
+    ($module:ident, $typ:ident) => {
+        pub(crate) mod $module {
+            use super::{Deserialize, Deserializer, Error, NameOrNumber, Serializer};
+            use crate::$typ;
+
+            pub fn serialize<S: Serializer>(value: &i32, serializer: S) -> Result<S::Ok, S::Error> {
+                match $typ::from_i32(*value) {
+                    Some(value) => serializer.serialize_str(value.as_str_name()),
+                    None => serializer.serialize_i32(*value),
+                }
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<i32, D::Error> {
+                match NameOrNumber::<i32>::deserialize(deserializer)? {
+                    NameOrNumber::Number(value) => Ok(value),
+                    NameOrNumber::Name(name) => $typ::from_str_name(&name)
+                        .map(|value| value as i32)
+                        .ok_or_else(|| {
+                            D::Error::custom(format!("unknown {} `{name}`", stringify!($typ)))
+                        }),
+                }
+            }
+        }
+    };
+}
+
+// one module for each enum of the proto files
+include!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/src/proto/enumerations.rs"
+));
+
+pub(crate) mod uint64 {
+    use super::{Deserialize, Deserializer, Error, NameOrNumber, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        match NameOrNumber::<u64>::deserialize(deserializer)? {
+            NameOrNumber::Number(value) => Ok(value),
+            NameOrNumber::Name(value) => value.parse().map_err(D::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn track() -> Track {
+        Track {
+            id: Some(TrackId {
+                id: "4uLU6hMCjMI75M1A2tKUQC".to_string(),
+                source: Service::Spotify as i32,
+                playlist_id: Some(PlaylistId {
+                    id: "37i9dQZF1DXcBWIGoYBM5M".to_string(),
+                    source: Service::Spotify as i32,
+                }),
+            }),
+            name: "Song".to_string(),
+            artist: "Artist".to_string(),
+            duration_millis: u64::MAX,
+            artists: vec![Artist {
+                id: Some(ArtistId {
+                    id: "0TnOYISbd1XYRBk9myaseg".to_string(),
+                    source: Service::Spotify as i32,
+                }),
+                name: "Artist".to_string(),
+            }],
+            album: Some(Album {
+                name: "Album".to_string(),
+                release_date: "2009-09".to_string(),
+                genres: vec!["house".to_string()],
+                ..Default::default()
+            }),
+            track_number: 3,
+            explicit: true,
+            external_ids: Some(ExternalIds {
+                isrc: "USRC17607839".to_string(),
+                ..Default::default()
+            }),
+            artwork: Some(Artwork {
+                url: "https://example.com/artwork.jpg".to_string(),
+                width: 640,
+                height: 640,
+            }),
+            info: Some(track::Info::SpotifyTrack(SpotifyTrack {
+                popularity: 42,
+                is_local: false,
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_track_round_trip() {
+        let playlist = Playlist {
+            id: Some(PlaylistId {
+                id: "PL123".to_string(),
+                source: Service::Youtube as i32,
+            }),
+            name: "Playlist".to_string(),
+            total: 1,
+            version: String::new(),
+            tracks: vec![track()],
+        };
+        let json = serde_json::to_value(&playlist).unwrap();
+        assert_eq!(json["id"]["source"], "YOUTUBE");
+        let track = &json["tracks"][0];
+        assert_eq!(track["durationMillis"], u64::MAX.to_string());
+        assert_eq!(track["id"]["playlistId"]["id"], "37i9dQZF1DXcBWIGoYBM5M");
+        assert_eq!(track["spotifyTrack"]["popularity"], 42);
+        assert_eq!(track["externalIds"]["isrc"], "USRC17607839");
+
+        let decoded: Playlist = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, playlist);
+    }
+
+    #[test]
+    fn test_lenient_decoding() {
+        // missing fields are defaults, and enums and 64 bit integers may be numbers
+        let json = r#"{"id": {"id": "abc", "source": 2}, "durationMillis": 1000}"#;
+        let track: Track = serde_json::from_str(json).unwrap();
+        assert_eq!(track.id().unwrap().source, Service::Youtube as i32);
+        assert_eq!(track.duration_millis, 1000);
+        assert_eq!(track.info, None);
+        assert!(serde_json::from_str::<TrackId>(r#"{"source": "TIDAL"}"#).is_err());
+    }
+
+    #[test]
+    fn test_progress_round_trip() {
+        let update = SyncProgressUpdate {
+            update: Some(sync_progress_update::Update::TrackStage(
+                TrackStageProgress {
+                    id: track().id,
+                    stage: TrackStage::TranscodeLowQuality as i32,
+                    progress: Some(track_stage_progress::Progress::Percent(0.5)),
+                },
+            )),
+        };
+        let json = serde_json::to_value(&update).unwrap();
+        assert_eq!(json["trackStage"]["stage"], "TRANSCODE_LOW_QUALITY");
+        assert_eq!(json["trackStage"]["percent"], 0.5);
+        assert_eq!(
+            serde_json::from_value::<SyncProgressUpdate>(json).unwrap(),
+            update
+        );
+
+        // a missing stage is not mistaken for a real one
+        let progress: TrackStageProgress = serde_json::from_str("{}").unwrap();
+        assert_eq!(progress.stage, TrackStage::Unspecified as i32);
+    }
+
+    #[test]
+    fn test_analysis_round_trip() {
+        let analysis = TrackAnalysis {
+            key: Some(MusicalKey {
+                tonic: 9,
+                mode: KeyMode::Minor as i32,
+                confidence: 0.5,
+            }),
+            cues: vec![CuePoint {
+                kind: CueKind::FirstDrop as i32,
+                position_secs: 32.5,
+            }],
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&analysis).unwrap();
+        assert_eq!(json["key"]["mode"], "MINOR");
+        assert_eq!(json["cues"][0]["kind"], "FIRST_DROP");
+        assert_eq!(json["cues"][0]["positionSecs"], 32.5);
+        assert_eq!(
+            serde_json::from_value::<TrackAnalysis>(json).unwrap(),
+            analysis
+        );
+    }
+}
@@ -1,6 +1,13 @@
+pub mod error;
+pub mod id;
+mod json;
 pub mod source;
 pub mod sink;
 
+pub use error::Error;
+pub use id::{resolve, ParseIdError, Resource};
+
+use std::collections::HashSet;
 pub use std::fmt;
 
 include!(concat!(
@@ -24,30 +31,165 @@ impl Track {
     pub fn id(&self) -> Result<&TrackId, TrackError> {
         self.id.as_ref().ok_or(TrackError::IdNotFound)
     }
+
+    /// Artist names, falling back to the joined display name
+    fn artist_names(&self) -> Vec<&str> {
+        if self.artists.is_empty() {
+            vec![self.artist.as_str()]
+        } else {
+            self.artists
+                .iter()
+                .map(|artist| artist.name.as_str())
+                .collect()
+        }
+    }
+
+    /// Confidence between 0 and 1 that `candidate` is a recording of this track
+    ///
+    /// Weighs how many words of the title and of the artist names the candidate
+    /// mentions, and how close its duration is. Sinks often put the artist into
+    /// the title (e.g. youtube videos), so both are searched for in the title and
+    /// the artists of the candidate. Unknown durations are not weighed.
+    pub fn match_score(&self, candidate: &Track) -> f32 {
+        const TITLE_WEIGHT: f32 = 0.5;
+        const ARTIST_WEIGHT: f32 = 0.3;
+        const DURATION_WEIGHT: f32 = 0.2;
+        // durations further apart than this do not match at all
+        const DURATION_TOLERANCE_MILLIS: f32 = 15_000.0;
+
+        let mut haystack = words(&candidate.name);
+        for name in candidate.artist_names() {
+            haystack.extend(words(name));
+        }
+        let coverage = |text: &str| {
+            let needles = words(text);
+            if needles.is_empty() {
+                return 0.0;
+            }
+            let found = needles.intersection(&haystack).count();
+            found as f32 / needles.len() as f32
+        };
+
+        let title = coverage(&self.name);
+        let artists = self.artist_names();
+        let artist = artists.iter().map(|name| coverage(name)).sum::<f32>() / artists.len() as f32;
+        let mut score = TITLE_WEIGHT * title + ARTIST_WEIGHT * artist;
+        let mut weights = TITLE_WEIGHT + ARTIST_WEIGHT;
+        if self.duration_millis > 0 && candidate.duration_millis > 0 {
+            let difference = self.duration_millis.abs_diff(candidate.duration_millis) as f32;
+            score += DURATION_WEIGHT * (1.0 - difference / DURATION_TOLERANCE_MILLIS).max(0.0);
+            weights += DURATION_WEIGHT;
+        }
+        score / weights
+    }
 }
 
-impl fmt::Display for TrackId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}:TRACK:{}",
-            Service::from_i32(self.source).unwrap().to_string(),
-            self.id,
-        )
+/// Lowercase alphanumeric words of `text`
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl TrackLink {
+    /// Links a source track to the sink track chosen by automatic matching
+    pub fn new(source_id: TrackId, sink_id: TrackId, score: f32, method: MatchMethod) -> Self {
+        let linked_at_millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|since| since.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            source_id: Some(source_id),
+            sink_id: Some(sink_id),
+            score,
+            method: method as i32,
+            alternatives: Vec::new(),
+            manual_override: false,
+            linked_at_millis,
+        }
+    }
+
+    /// Whether this link may replace a previous link of the same track
+    ///
+    /// Manual overrides are only ever replaced by other manual overrides.
+    pub fn replaces(&self, previous: &TrackLink) -> bool {
+        self.manual_override || !previous.manual_override
     }
 }
 
-impl fmt::Display for PlaylistId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}:PLAYLIST:{}",
-            Service::from_i32(self.source).unwrap().to_string(),
-            self.id,
-        )
+impl PlaylistSnapshot {
+    /// Snapshot of the given version of a playlist and its tracks
+    pub fn new<'a>(
+        id: PlaylistId,
+        version: String,
+        tracks: impl IntoIterator<Item = &'a Track>,
+    ) -> Self {
+        Self {
+            id: Some(id),
+            version,
+            tracks: tracks
+                .into_iter()
+                .filter_map(|track| track.id.to_owned())
+                .collect(),
+        }
+    }
+
+    /// Whether the playlist is unchanged since this snapshot
+    ///
+    /// Playlists without a version are never considered current.
+    pub fn is_current(&self, version: &str) -> bool {
+        !version.is_empty() && self.version == version
+    }
+
+    /// Tracks added to and removed from the playlist since this snapshot
+    pub fn diff(&self, tracks: &[Track]) -> PlaylistDiff {
+        let previous: HashSet<&TrackId> = self.tracks.iter().collect();
+        let current: HashSet<&TrackId> = tracks.iter().filter_map(|t| t.id.as_ref()).collect();
+        PlaylistDiff {
+            added: tracks
+                .iter()
+                .filter(|track| match track.id {
+                    Some(ref id) => !previous.contains(id),
+                    None => true,
+                })
+                .cloned()
+                .collect(),
+            removed: self
+                .tracks
+                .iter()
+                .filter(|id| !current.contains(id))
+                .cloned()
+                .collect(),
+        }
     }
 }
 
+impl PlaylistDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Displays an ID as `SERVICE:KIND:id`, which `FromStr` parses back
+macro_rules! display_id {
+    ($typ:ident, $kind:literal) => {
+        impl fmt::Display for $typ {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let service =
+                    Service::from_i32(self.source).map_or("UNKNOWN", |s| s.as_str_name());
+                write!(f, "{}:{}:{}", service, $kind, self.id)
+            }
+        }
+    };
+}
+
+display_id!(TrackId, "TRACK");
+display_id!(PlaylistId, "PLAYLIST");
+display_id!(UserId, "USER");
+display_id!(AlbumId, "ALBUM");
+display_id!(ArtistId, "ARTIST");
+
 // pub trait ConvertFrom<T>
 // where
 //     Self: Sized,
@@ -81,3 +223,64 @@ impl fmt::Display for PlaylistId {
 //         TryConvertFrom::try_convert_from(self)
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::{Artist, PlaylistId, PlaylistSnapshot, Service, Track, TrackId};
+
+    fn track(id: &str) -> Track {
+        Track {
+            id: Some(TrackId {
+                id: id.to_string(),
+                playlist_id: None,
+                source: Service::Spotify as i32,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_playlist_diff() {
+        let id = PlaylistId {
+            id: "37i9dQZF1DXcBWIGoYBM5M".to_string(),
+            source: Service::Spotify as i32,
+        };
+        let previous = [track("a"), track("b"), track("c")];
+        let snapshot = PlaylistSnapshot::new(id, "v1".to_string(), &previous);
+        assert!(snapshot.is_current("v1"));
+        assert!(!snapshot.is_current("v2"));
+        assert!(!snapshot.is_current(""));
+
+        let diff = snapshot.diff(&[track("b"), track("d"), track("a")]);
+        assert_eq!(diff.added, vec![track("d")]);
+        assert_eq!(diff.removed, vec![track("c").id.unwrap()]);
+        assert!(snapshot.diff(&previous).is_empty());
+    }
+
+    #[test]
+    fn test_match_score() {
+        let source = Track {
+            name: "Strobe - Radio Edit".to_string(),
+            artists: vec![Artist {
+                id: None,
+                name: "deadmau5".to_string(),
+            }],
+            duration_millis: 210_000,
+            ..track("4uLU6hMCjMI75M1A2tKUQC")
+        };
+        let video = |name: &str, duration_millis| Track {
+            name: name.to_string(),
+            duration_millis,
+            ..track("tKi9Z-f6qX4")
+        };
+
+        let exact = source.match_score(&video("deadmau5 - Strobe (Radio Edit)", 211_000));
+        assert!(exact > 0.95, "{exact}");
+        let unknown_duration = source.match_score(&video("deadmau5 - Strobe (Radio Edit)", 0));
+        assert_eq!(unknown_duration, 1.0);
+        let extended = source.match_score(&video("deadmau5 - Strobe", 637_000));
+        assert!(extended < exact, "{extended}");
+        let unrelated = source.match_score(&video("Never Gonna Give You Up", 213_000));
+        assert!(unrelated < 0.25, "{unrelated}");
+    }
+}
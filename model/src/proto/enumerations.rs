@@ -0,0 +1,5 @@
+enumeration!(service, Service);
+enumeration!(match_method, MatchMethod);
+enumeration!(key_mode, KeyMode);
+enumeration!(cue_kind, CueKind);
+enumeration!(track_stage, TrackStage);
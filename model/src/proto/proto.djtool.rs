@@ -1,13 +1,18 @@
-#[derive(serde::Serialize, serde::Deserialize, Hash, Eq)]
+#[derive(Hash, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserId {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
     #[prost(enumeration = "Service", tag = "10")]
+    #[serde(with = "crate::json::service")]
     pub source: i32,
 }
-#[derive(serde::Serialize, serde::Deserialize, Hash, Eq)]
+#[derive(Hash, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TrackId {
@@ -16,17 +21,47 @@ pub struct TrackId {
     #[prost(message, optional, tag = "2")]
     pub playlist_id: ::core::option::Option<PlaylistId>,
     #[prost(enumeration = "Service", tag = "10")]
+    #[serde(with = "crate::json::service")]
     pub source: i32,
 }
-#[derive(serde::Serialize, serde::Deserialize, Hash, Eq)]
+#[derive(Hash, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PlaylistId {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
     #[prost(enumeration = "Service", tag = "10")]
+    #[serde(with = "crate::json::service")]
     pub source: i32,
 }
+#[derive(Hash, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AlbumId {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(enumeration = "Service", tag = "10")]
+    #[serde(with = "crate::json::service")]
+    pub source: i32,
+}
+#[derive(Hash, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ArtistId {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(enumeration = "Service", tag = "10")]
+    #[serde(with = "crate::json::service")]
+    pub source: i32,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SpotifyUserLoginCallbackPkce {
@@ -35,14 +70,19 @@ pub struct SpotifyUserLoginCallbackPkce {
     #[prost(string, tag = "2")]
     pub state: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SpotifyUserLoginCallback {
     #[prost(oneof = "spotify_user_login_callback::Method", tags = "1")]
+    #[serde(flatten)]
     pub method: ::core::option::Option<spotify_user_login_callback::Method>,
 }
 /// Nested message and enum types in `SpotifyUserLoginCallback`.
 pub mod spotify_user_login_callback {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Method {
@@ -50,14 +90,19 @@ pub mod spotify_user_login_callback {
         Pkce(super::SpotifyUserLoginCallbackPkce),
     }
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserLoginCallback {
     #[prost(oneof = "user_login_callback::Login", tags = "1")]
+    #[serde(flatten)]
     pub login: ::core::option::Option<user_login_callback::Login>,
 }
 /// Nested message and enum types in `UserLoginCallback`.
 pub mod user_login_callback {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Login {
@@ -65,6 +110,8 @@ pub mod user_login_callback {
         SpotifyLogin(super::SpotifyUserLoginCallback),
     }
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Artwork {
@@ -75,18 +122,97 @@ pub struct Artwork {
     #[prost(uint32, tag = "3")]
     pub height: u32,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct User {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<UserId>,
+    #[prost(string, tag = "2")]
+    pub display_name: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub avatar: ::core::option::Option<Artwork>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TrackPreview {
     #[prost(string, tag = "1")]
     pub url: ::prost::alloc::string::String,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Artist {
+    /// unset if the service does not identify the artist
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<ArtistId>,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Album {
+    /// unset if the service does not identify the album
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<AlbumId>,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub artists: ::prost::alloc::vec::Vec<Artist>,
+    /// e.g. album, single or compilation
+    #[prost(string, tag = "4")]
+    pub album_type: ::prost::alloc::string::String,
+    /// as precise as known, e.g. 2009, 2009-09 or 2009-09-07
+    #[prost(string, tag = "5")]
+    pub release_date: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "6")]
+    pub total_tracks: u32,
+    #[prost(string, tag = "7")]
+    pub label: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "8")]
+    pub genres: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExternalIds {
+    #[prost(string, tag = "1")]
+    pub isrc: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub ean: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub upc: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct SpotifyTrack {}
+pub struct SpotifyTrack {
+    #[prost(uint32, tag = "1")]
+    pub popularity: u32,
+    #[prost(bool, tag = "2")]
+    pub is_local: bool,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct YoutubeTrack {}
+pub struct YoutubeTrack {
+    #[prost(string, tag = "1")]
+    pub channel: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    #[serde(with = "crate::json::uint64")]
+    pub view_count: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Track {
@@ -94,19 +220,38 @@ pub struct Track {
     pub id: ::core::option::Option<TrackId>,
     #[prost(string, tag = "100")]
     pub name: ::prost::alloc::string::String,
+    /// all artist names joined for display
     #[prost(string, tag = "101")]
     pub artist: ::prost::alloc::string::String,
     #[prost(uint64, tag = "102")]
+    #[serde(with = "crate::json::uint64")]
     pub duration_millis: u64,
+    #[prost(message, repeated, tag = "103")]
+    pub artists: ::prost::alloc::vec::Vec<Artist>,
+    #[prost(message, optional, tag = "104")]
+    pub album: ::core::option::Option<Album>,
+    #[prost(uint32, tag = "105")]
+    pub track_number: u32,
+    #[prost(uint32, tag = "106")]
+    pub disc_number: u32,
+    #[prost(bool, tag = "107")]
+    pub explicit: bool,
+    #[prost(message, optional, tag = "108")]
+    pub external_ids: ::core::option::Option<ExternalIds>,
+    #[prost(string, repeated, tag = "109")]
+    pub genres: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     #[prost(message, optional, tag = "200")]
     pub artwork: ::core::option::Option<Artwork>,
     #[prost(message, optional, tag = "201")]
     pub preview: ::core::option::Option<TrackPreview>,
     #[prost(oneof = "track::Info", tags = "301, 302")]
+    #[serde(flatten)]
     pub info: ::core::option::Option<track::Info>,
 }
 /// Nested message and enum types in `Track`.
 pub mod track {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Info {
@@ -116,6 +261,8 @@ pub mod track {
         YoutubeTrack(super::YoutubeTrack),
     }
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Playlist {
@@ -125,9 +272,186 @@ pub struct Playlist {
     pub name: ::prost::alloc::string::String,
     #[prost(uint32, tag = "3")]
     pub total: u32,
+    /// changes whenever the tracks change, empty if the source has no versions
+    #[prost(string, tag = "4")]
+    pub version: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "100")]
     pub tracks: ::prost::alloc::vec::Vec<Track>,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrackCandidate {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<TrackId>,
+    #[prost(float, tag = "2")]
+    pub score: f32,
+}
+/// Records which sink track a source track was resolved to
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrackLink {
+    #[prost(message, optional, tag = "1")]
+    pub source_id: ::core::option::Option<TrackId>,
+    #[prost(message, optional, tag = "2")]
+    pub sink_id: ::core::option::Option<TrackId>,
+    #[prost(float, tag = "3")]
+    pub score: f32,
+    #[prost(enumeration = "MatchMethod", tag = "4")]
+    #[serde(with = "crate::json::match_method")]
+    pub method: i32,
+    /// candidates that were considered but not chosen, best first
+    #[prost(message, repeated, tag = "5")]
+    pub alternatives: ::prost::alloc::vec::Vec<TrackCandidate>,
+    /// chosen by the user, and never replaced by automatic matching
+    #[prost(bool, tag = "6")]
+    pub manual_override: bool,
+    /// milliseconds since the unix epoch
+    #[prost(uint64, tag = "7")]
+    #[serde(with = "crate::json::uint64")]
+    pub linked_at_millis: u64,
+}
+/// Tracks of a playlist as of its last sync
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaylistSnapshot {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<PlaylistId>,
+    #[prost(string, tag = "2")]
+    pub version: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub tracks: ::prost::alloc::vec::Vec<TrackId>,
+}
+/// Changes of a playlist since its last snapshot
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaylistDiff {
+    #[prost(message, repeated, tag = "1")]
+    pub added: ::prost::alloc::vec::Vec<Track>,
+    #[prost(message, repeated, tag = "2")]
+    pub removed: ::prost::alloc::vec::Vec<TrackId>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RateLimit {
+    /// unset if the limit is not documented
+    #[prost(message, optional, tag = "1")]
+    pub requests: ::core::option::Option<u32>,
+    #[prost(uint32, tag = "2")]
+    pub window_secs: u32,
+}
+/// What a source or sink supports, such that callers only offer what works
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Capabilities {
+    #[prost(bool, tag = "1")]
+    pub search: bool,
+    #[prost(bool, tag = "2")]
+    pub playlists: bool,
+    /// saved tracks, albums and followed artists of the current user
+    #[prost(bool, tag = "3")]
+    pub user_library: bool,
+    #[prost(bool, tag = "4")]
+    pub previews: bool,
+    #[prost(bool, tag = "5")]
+    pub downloads: bool,
+    #[prost(bool, tag = "6")]
+    pub requires_login: bool,
+    #[prost(message, optional, tag = "7")]
+    pub rate_limit: ::core::option::Option<RateLimit>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tempo {
+    #[prost(float, tag = "1")]
+    pub bpm: f32,
+    /// normalized autocorrelation of the onsets at the beat period in [0, 1]
+    #[prost(float, tag = "2")]
+    pub confidence: f32,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MusicalKey {
+    /// pitch class of the tonic, where 0 is C
+    #[prost(uint32, tag = "1")]
+    pub tonic: u32,
+    #[prost(enumeration = "KeyMode", tag = "2")]
+    #[serde(with = "crate::json::key_mode")]
+    pub mode: i32,
+    #[prost(float, tag = "3")]
+    pub confidence: f32,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TempoChange {
+    /// time of the first beat with the new tempo in seconds
+    #[prost(double, tag = "1")]
+    pub position_secs: f64,
+    #[prost(float, tag = "2")]
+    pub bpm: f32,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BeatGrid {
+    /// time of the first beat in seconds
+    #[prost(double, tag = "1")]
+    pub anchor_secs: f64,
+    #[prost(float, tag = "2")]
+    pub bpm: f32,
+    #[prost(uint32, tag = "3")]
+    pub beats_per_bar: u32,
+    /// index of the first downbeat counted from the anchor, if bars were detected
+    #[prost(message, optional, tag = "4")]
+    pub downbeat: ::core::option::Option<u32>,
+    #[prost(message, repeated, tag = "5")]
+    pub tempo_changes: ::prost::alloc::vec::Vec<TempoChange>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CuePoint {
+    #[prost(enumeration = "CueKind", tag = "1")]
+    #[serde(with = "crate::json::cue_kind")]
+    pub kind: i32,
+    #[prost(double, tag = "2")]
+    pub position_secs: f64,
+}
+/// Musical properties of a track that are stored with its library entry
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrackAnalysis {
+    #[prost(message, optional, tag = "1")]
+    pub tempo: ::core::option::Option<Tempo>,
+    #[prost(message, optional, tag = "2")]
+    pub key: ::core::option::Option<MusicalKey>,
+    #[prost(message, optional, tag = "3")]
+    pub beat_grid: ::core::option::Option<BeatGrid>,
+    /// suggested cue points for exporters
+    #[prost(message, repeated, tag = "4")]
+    pub cues: ::prost::alloc::vec::Vec<CuePoint>,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum Service {
@@ -157,3 +481,345 @@ impl Service {
         }
     }
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum MatchMethod {
+    Unspecified = 0,
+    Best = 1,
+    Fast = 2,
+    First = 3,
+}
+impl MatchMethod {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            MatchMethod::Unspecified => "MATCH_METHOD_UNSPECIFIED",
+            MatchMethod::Best => "BEST",
+            MatchMethod::Fast => "FAST",
+            MatchMethod::First => "FIRST",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "MATCH_METHOD_UNSPECIFIED" => Some(Self::Unspecified),
+            "BEST" => Some(Self::Best),
+            "FAST" => Some(Self::Fast),
+            "FIRST" => Some(Self::First),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum KeyMode {
+    Unspecified = 0,
+    Major = 1,
+    Minor = 2,
+}
+impl KeyMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            KeyMode::Unspecified => "KEY_MODE_UNSPECIFIED",
+            KeyMode::Major => "MAJOR",
+            KeyMode::Minor => "MINOR",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "KEY_MODE_UNSPECIFIED" => Some(Self::Unspecified),
+            "MAJOR" => Some(Self::Major),
+            "MINOR" => Some(Self::Minor),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CueKind {
+    Unspecified = 0,
+    IntroEnd = 1,
+    FirstDrop = 2,
+    Breakdown = 3,
+    OutroStart = 4,
+}
+impl CueKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CueKind::Unspecified => "CUE_KIND_UNSPECIFIED",
+            CueKind::IntroEnd => "INTRO_END",
+            CueKind::FirstDrop => "FIRST_DROP",
+            CueKind::Breakdown => "BREAKDOWN",
+            CueKind::OutroStart => "OUTRO_START",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CUE_KIND_UNSPECIFIED" => Some(Self::Unspecified),
+            "INTRO_END" => Some(Self::IntroEnd),
+            "FIRST_DROP" => Some(Self::FirstDrop),
+            "BREAKDOWN" => Some(Self::Breakdown),
+            "OUTRO_START" => Some(Self::OutroStart),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncCounters {
+    #[prost(uint64, tag = "1")]
+    #[serde(with = "crate::json::uint64")]
+    pub playlists: u64,
+    #[prost(uint64, tag = "2")]
+    #[serde(with = "crate::json::uint64")]
+    pub playlists_succeeded: u64,
+    #[prost(uint64, tag = "3")]
+    #[serde(with = "crate::json::uint64")]
+    pub playlists_failed: u64,
+    #[prost(uint64, tag = "4")]
+    #[serde(with = "crate::json::uint64")]
+    pub tracks: u64,
+    #[prost(uint64, tag = "5")]
+    #[serde(with = "crate::json::uint64")]
+    pub tracks_succeeded: u64,
+    #[prost(uint64, tag = "6")]
+    #[serde(with = "crate::json::uint64")]
+    pub tracks_failed: u64,
+    #[prost(uint64, tag = "7")]
+    #[serde(with = "crate::json::uint64")]
+    pub tracks_in_progress: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaylistFetchProgress {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<PlaylistId>,
+    #[prost(uint64, tag = "2")]
+    #[serde(with = "crate::json::uint64")]
+    pub fetched: u64,
+    #[prost(uint64, tag = "3")]
+    #[serde(with = "crate::json::uint64")]
+    pub failed: u64,
+    #[prost(uint64, tag = "4")]
+    #[serde(with = "crate::json::uint64")]
+    pub in_progress: u64,
+    /// zero if unknown
+    #[prost(uint64, tag = "5")]
+    #[serde(with = "crate::json::uint64")]
+    pub total: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaylistCompleted {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<PlaylistId>,
+    #[prost(uint64, tag = "2")]
+    #[serde(with = "crate::json::uint64")]
+    pub succeeded: u64,
+    #[prost(uint64, tag = "3")]
+    #[serde(with = "crate::json::uint64")]
+    pub failed: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaylistFailed {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<PlaylistId>,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ByteProgress {
+    #[prost(uint64, tag = "1")]
+    #[serde(with = "crate::json::uint64")]
+    pub downloaded: u64,
+    /// zero if unknown
+    #[prost(uint64, tag = "2")]
+    #[serde(with = "crate::json::uint64")]
+    pub total: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrackStageProgress {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<TrackId>,
+    #[prost(enumeration = "TrackStage", tag = "2")]
+    #[serde(with = "crate::json::track_stage")]
+    pub stage: i32,
+    #[prost(oneof = "track_stage_progress::Progress", tags = "3, 4")]
+    #[serde(flatten)]
+    pub progress: ::core::option::Option<track_stage_progress::Progress>,
+}
+/// Nested message and enum types in `TrackStageProgress`.
+pub mod track_stage_progress {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Progress {
+        #[prost(message, tag = "3")]
+        Bytes(super::ByteProgress),
+        #[prost(float, tag = "4")]
+        Percent(f32),
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrackCompleted {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<TrackId>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrackFailed {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<TrackId>,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncProgressUpdate {
+    #[prost(oneof = "sync_progress_update::Update", tags = "1, 2, 3, 4, 5, 6, 7")]
+    #[serde(flatten)]
+    pub update: ::core::option::Option<sync_progress_update::Update>,
+}
+/// Nested message and enum types in `SyncProgressUpdate`.
+pub mod sync_progress_update {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Update {
+        #[prost(message, tag = "1")]
+        Counters(super::SyncCounters),
+        #[prost(message, tag = "2")]
+        PlaylistFetch(super::PlaylistFetchProgress),
+        #[prost(message, tag = "3")]
+        PlaylistCompleted(super::PlaylistCompleted),
+        #[prost(message, tag = "4")]
+        PlaylistFailed(super::PlaylistFailed),
+        #[prost(message, tag = "5")]
+        TrackStage(super::TrackStageProgress),
+        #[prost(message, tag = "6")]
+        TrackCompleted(super::TrackCompleted),
+        #[prost(message, tag = "7")]
+        TrackFailed(super::TrackFailed),
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrackSyncDesc {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub sink: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlaylistSyncDesc {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub sink: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub sources: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(message, repeated, tag = "2")]
+    pub tracks: ::prost::alloc::vec::Vec<TrackSyncDesc>,
+    /// oneof request {
+    #[prost(message, repeated, tag = "3")]
+    pub playlists: ::prost::alloc::vec::Vec<PlaylistSyncDesc>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TrackStage {
+    Unspecified = 0,
+    Match = 1,
+    DownloadPreview = 2,
+    DownloadTrack = 3,
+    DownloadArtwork = 4,
+    TranscodeHighQuality = 5,
+    TranscodeLowQuality = 6,
+    Tag = 7,
+    Move = 8,
+}
+impl TrackStage {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            TrackStage::Unspecified => "TRACK_STAGE_UNSPECIFIED",
+            TrackStage::Match => "MATCH",
+            TrackStage::DownloadPreview => "DOWNLOAD_PREVIEW",
+            TrackStage::DownloadTrack => "DOWNLOAD_TRACK",
+            TrackStage::DownloadArtwork => "DOWNLOAD_ARTWORK",
+            TrackStage::TranscodeHighQuality => "TRANSCODE_HIGH_QUALITY",
+            TrackStage::TranscodeLowQuality => "TRANSCODE_LOW_QUALITY",
+            TrackStage::Tag => "TAG",
+            TrackStage::Move => "MOVE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "TRACK_STAGE_UNSPECIFIED" => Some(Self::Unspecified),
+            "MATCH" => Some(Self::Match),
+            "DOWNLOAD_PREVIEW" => Some(Self::DownloadPreview),
+            "DOWNLOAD_TRACK" => Some(Self::DownloadTrack),
+            "DOWNLOAD_ARTWORK" => Some(Self::DownloadArtwork),
+            "TRANSCODE_HIGH_QUALITY" => Some(Self::TranscodeHighQuality),
+            "TRANSCODE_LOW_QUALITY" => Some(Self::TranscodeLowQuality),
+            "TAG" => Some(Self::Tag),
+            "MOVE" => Some(Self::Move),
+            _ => None,
+        }
+    }
+}
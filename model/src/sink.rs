@@ -15,17 +15,27 @@ pub enum Method {
     First,
 }
 
+impl From<&Method> for super::MatchMethod {
+    fn from(method: &Method) -> Self {
+        match method {
+            Method::Best { .. } => Self::Best,
+            Method::Fast { .. } => Self::Fast,
+            Method::First => Self::First,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DownloadedTrack {
     pub track: super::Track,
     pub output_path: PathBuf,
+    /// Decision that resolved the source track to the downloaded track, if known
+    pub link: Option<super::TrackLink>,
 }
 
-#[derive(Debug, Clone)]
-pub struct QueryProgress {}
+pub use super::source::QueryProgress;
 
-#[derive(thiserror::Error, Debug)]
-pub enum Error {}
+pub use super::error::Error;
 
 #[derive(Debug)]
 pub struct DownloadProgress {}
@@ -34,6 +44,8 @@ pub struct DownloadProgress {}
 
 #[async_trait::async_trait]
 pub trait Sink: Send + Sync + 'static {
+    fn capabilities(&self) -> super::Capabilities;
+
     async fn audio_download_url(&self, track: &super::Track) -> Result<(String, String), Error>;
 
     async fn download(
@@ -1,20 +1,19 @@
 use futures::stream::Stream;
 use std::pin::Pin;
+use std::time::Duration;
 
 pub type PlaylistStream<'a> =
     Pin<Box<dyn Stream<Item = Result<super::Playlist, Error>> + 'a + Send>>;
 
 pub type TrackStream<'a> = Pin<Box<dyn Stream<Item = Result<super::Track, Error>> + 'a + Send>>;
 
+pub type ArtistStream<'a> = Pin<Box<dyn Stream<Item = Result<super::Artist, Error>> + 'a + Send>>;
+
+pub type AlbumStream<'a> = Pin<Box<dyn Stream<Item = Result<super::Album, Error>> + 'a + Send>>;
+
 pub type SearchResultStream<'a, R> = Pin<Box<dyn Stream<Item = Result<R, Error>> + 'a + Send>>;
 
-#[derive(thiserror::Error, Debug)]
-pub enum Error {
-    #[error("not found")]
-    NotFound,
-    #[error("source error: `{0:?}`")]
-    Custom(#[source] Box<dyn std::error::Error + Send + Sync>),
-}
+pub use super::error::Error;
 
 #[derive(Clone, Debug)]
 pub enum SearchFilterYear {
@@ -73,19 +72,58 @@ impl SearchQuery {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct QueryProgress {}
+impl std::fmt::Display for SearchQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let filters = self
+            .query
+            .iter()
+            .map(|filter| match filter {
+                SearchQueryFilter::Album(album) => format!("album:{}", album),
+                SearchQueryFilter::Artist(artist) => format!("artist:{}", artist),
+                SearchQueryFilter::Track(track) => format!("track:{}", track),
+                SearchQueryFilter::Year(SearchFilterYear::Year(year)) => format!("year:{}", year),
+                SearchQueryFilter::Year(SearchFilterYear::Range(start, end)) => {
+                    format!("year:{}-{}", start, end)
+                }
+                SearchQueryFilter::Genre(genre) => format!("genre:{}", genre),
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{}", filters.join(" "))
+    }
+}
+
+/// Progress of a paginated query, reported after every fetched page
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryProgress {
+    /// Query string as sent to the service
+    pub query: String,
+    pub pages: usize,
+    /// Items received so far, including those of the latest page
+    pub items: usize,
+    /// Total number of items, if the service reports it
+    pub total: Option<usize>,
+    pub elapsed: Duration,
+}
 
 #[async_trait::async_trait]
 pub trait Source {
     fn id(&self) -> super::Service;
+
+    fn capabilities(&self) -> super::Capabilities;
+
     // get user info (username, profile picture)
+    async fn current_user(&self) -> Result<super::User, Error>;
 
     // get track and playlist info
     async fn playlist_by_id(&self, id: &String) -> Result<Option<super::Playlist>, Error>;
 
     async fn track_by_id(&self, id: &String) -> Result<Option<super::Track>, Error>;
 
+    /// Current version of a playlist, without fetching its tracks
+    ///
+    /// Returns `None` if the source does not version playlists.
+    async fn playlist_version(&self, id: &super::PlaylistId) -> Result<Option<String>, Error>;
+
     async fn search(
         &self,
         query: SearchQuery,
@@ -108,6 +146,29 @@ pub trait Source {
         playlist_id: super::Playlist,
     ) -> Result<TrackStream, Error>;
 
+    // get stream of the library of the current user
+    fn saved_tracks_stream<'a>(&'a self) -> Result<TrackStream<'a>, Error>;
+
+    fn followed_artists_stream<'a>(&'a self) -> Result<ArtistStream<'a>, Error>;
+
+    fn saved_albums_stream<'a>(&'a self) -> Result<AlbumStream<'a>, Error>;
+
+    // get stream of albums and artist discographies
+    fn album_tracks_stream<'a>(
+        &'a self,
+        album_id: super::AlbumId,
+    ) -> Result<TrackStream<'a>, Error>;
+
+    fn artist_albums_stream<'a>(
+        &'a self,
+        artist_id: super::ArtistId,
+    ) -> Result<AlbumStream<'a>, Error>;
+
+    async fn artist_top_tracks(
+        &self,
+        artist_id: &super::ArtistId,
+    ) -> Result<Vec<super::Track>, Error>;
+
     // get stream of tracks based on name
     fn search_stream<'a>(
         &'a self,
@@ -130,3 +191,20 @@ pub trait Source {
 
     // get stream of playlist tracks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchFilterYear, SearchQuery, SearchQueryFilter};
+
+    #[test]
+    fn test_search_query_string() {
+        let mut query = SearchQuery::track("Windowlicker", Some("Aphex Twin"));
+        query
+            .query
+            .push(SearchQueryFilter::Year(SearchFilterYear::Range(1990, 2000)));
+        assert_eq!(
+            query.to_string(),
+            "track:Windowlicker artist:Aphex Twin year:1990-2000"
+        );
+    }
+}
@@ -32,10 +32,14 @@ impl CLI {
 
             let oauth = OAuth {
                 redirect_uri,
-                scopes: crate::scopes!("playlist-read-private"),
+                scopes: crate::scopes!(
+                    "playlist-read-private",
+                    "user-read-private",
+                    "user-library-read",
+                    "user-follow-read"
+                ),
                 ..Default::default()
             };
-            dbg!(&oauth);
             // tool.connect_spotify(creds, oauth).await.unwrap();
             // println!("connected");
             // tool
@@ -1,6 +1,41 @@
 use clap::Parser;
+use djtool_model as model;
 use std::path::PathBuf;
 
+/// Parses a URL, URI or ID, where bare IDs are assumed to be Spotify IDs
+fn parse_id<T>(id: &str, spotify_id: impl FnOnce(String) -> T) -> Result<T, model::ParseIdError>
+where
+    T: std::str::FromStr<Err = model::ParseIdError>,
+{
+    match id.parse() {
+        Err(model::ParseIdError::Invalid(_)) if id.chars().all(char::is_alphanumeric) => {
+            Ok(spotify_id(id.to_string()))
+        }
+        result => result,
+    }
+}
+
+fn parse_playlist_id(id: &str) -> Result<model::PlaylistId, model::ParseIdError> {
+    parse_id(id, |id| model::PlaylistId {
+        id,
+        source: model::Service::Spotify as i32,
+    })
+}
+
+fn parse_album_id(id: &str) -> Result<model::AlbumId, model::ParseIdError> {
+    parse_id(id, |id| model::AlbumId {
+        id,
+        source: model::Service::Spotify as i32,
+    })
+}
+
+fn parse_artist_id(id: &str) -> Result<model::ArtistId, model::ParseIdError> {
+    parse_id(id, |id| model::ArtistId {
+        id,
+        source: model::Service::Spotify as i32,
+    })
+}
+
 #[derive(Parser, Debug, Clone)]
 pub struct PerTrackListOptions {
     #[clap(long = "artwork")]
@@ -117,18 +152,63 @@ pub struct PlaylistOptions {
     #[clap(subcommand)]
     pub command: PlaylistCommand,
 
-    #[clap(long = "playlist-id", alias = "id", env = "SPOTIFY_PLAYLIST_ID")]
-    pub id: Option<String>,
+    #[clap(
+        long = "playlist-id",
+        alias = "id",
+        help = "playlist URL, URI or ID",
+        env = "SPOTIFY_PLAYLIST_ID",
+        parse(try_from_str = parse_playlist_id)
+    )]
+    pub id: Option<model::PlaylistId>,
     #[clap(long = "name", env = "SPOTIFY_PLAYLIST_NAME")]
     pub name: Option<String>,
 }
 
+#[derive(Parser, Debug, Clone)]
+pub struct AlbumOptions {
+    #[clap(subcommand)]
+    pub command: PlaylistCommand,
+
+    #[clap(
+        long = "album-id",
+        alias = "id",
+        help = "album URL, URI or ID",
+        env = "SPOTIFY_ALBUM_ID",
+        parse(try_from_str = parse_album_id)
+    )]
+    pub id: model::AlbumId,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ArtistOptions {
+    #[clap(subcommand)]
+    pub command: PlaylistCommand,
+
+    #[clap(
+        long = "artist-id",
+        alias = "id",
+        help = "artist URL, URI or ID",
+        env = "SPOTIFY_ARTIST_ID",
+        parse(try_from_str = parse_artist_id)
+    )]
+    pub id: model::ArtistId,
+    #[clap(
+        long = "top-tracks",
+        help = "only the top tracks instead of all albums"
+    )]
+    pub top_tracks: bool,
+}
+
 #[derive(Parser, Debug, Clone)]
 pub enum Command {
     #[clap(name = "track", about = "")]
     Track(TrackOptions),
     #[clap(name = "playlist", about = "")]
     Playlist(PlaylistOptions),
+    #[clap(name = "album", about = "")]
+    Album(AlbumOptions),
+    #[clap(name = "artist", about = "")]
+    Artist(ArtistOptions),
 }
 
 #[derive(Parser, Debug, Clone)]
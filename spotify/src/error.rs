@@ -23,6 +23,10 @@ pub enum ApiError {
     InvalidID(#[from] rspotify_model::IdError),
     #[error("invalid media type (neither track or episode)")]
     InvalidMediaType,
+    #[error("rate limited (retry after {retry_after:?})")]
+    RateLimited {
+        retry_after: Option<std::time::Duration>,
+    },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -44,3 +48,38 @@ pub enum Error {
     #[error("unknown spotify error: `{0:?}`")]
     Unknown(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
+
+impl From<ApiError> for djtool_model::Error {
+    fn from(err: ApiError) -> Self {
+        use reqwest::StatusCode;
+        match err {
+            ApiError::RateLimited { retry_after } => Self::RateLimited { retry_after },
+            ApiError::Http(err) => match err.status() {
+                Some(StatusCode::UNAUTHORIZED) => Self::Unauthorized,
+                Some(StatusCode::NOT_FOUND) => Self::NotFound,
+                Some(StatusCode::TOO_MANY_REQUESTS) => Self::RateLimited { retry_after: None },
+                Some(status) if status.is_server_error() => Self::Transient(err.into()),
+                _ if err.is_decode() => Self::Parse(err.into()),
+                _ if err.is_timeout() || err.is_connect() || err.is_request() => {
+                    Self::Transient(err.into())
+                }
+                _ => Self::Custom(err.into()),
+            },
+            err => Self::Custom(err.into()),
+        }
+    }
+}
+
+impl From<Error> for djtool_model::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Auth(AuthError::RequireUserLogin { auth_url }) => {
+                Self::RequiresLogin { auth_url }
+            }
+            Error::Auth(_) => Self::Unauthorized,
+            Error::Api(err) => err.into(),
+            Error::NotFound => Self::NotFound,
+            err => Self::Custom(err.into()),
+        }
+    }
+}
@@ -62,29 +62,17 @@ impl Spotify {
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> Result<rspotify_model::SearchResult, Error> {
-        // let user_id = model::UserId::from_id(&user_id)?;
-        let query = HashMap::<&str, Value>::from_iter(
+        let params = HashMap::<&str, Value>::from_iter(
             vec![
+                Some(("q", search_query.to_string().into())),
+                Some(("type", "track".into())),
                 limit.map(|limit| ("limit", limit.into())),
                 offset.map(|offset| ("offset", offset.into())),
             ]
             .into_iter()
             .filter_map(|e| e),
         );
-        let headers = self.auth_headers().await;
-        let r = self
-            .client
-            .get(api!("search")?)
-            .headers(self.auth_headers().await)
-            .query(&query)
-            .send()
-            .await
-            .map_err(ApiError::from)?;
-        // crate::debug!(r);
-        // .json::<model::spotify::Page<model::spotify::SimplifiedPlaylist>>()
-        // .await
-        // .map_err(Into::into)
-        unimplemented!();
+        self.get_json("search".to_string(), &params).await
     }
 
     pub async fn user_playlists_page(
@@ -205,6 +193,159 @@ impl Spotify {
             .map_err(Error::from)
     }
 
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        path: String,
+        params: &HashMap<&str, Value>,
+    ) -> Result<T, Error> {
+        let url = api!(path).map_err(ApiError::from)?;
+        let res = self
+            .client
+            .get(url)
+            .headers(self.auth_headers().await)
+            .query(params)
+            .send()
+            .await
+            .map_err(ApiError::from)?;
+        if res.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = res
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .map(std::time::Duration::from_secs);
+            return Err(ApiError::RateLimited { retry_after }.into());
+        }
+        let res = res.error_for_status().map_err(ApiError::from)?;
+        res.json::<T>().await.map_err(ApiError::from).map_err(Error::from)
+    }
+
+    pub async fn me(&self) -> Result<model::spotify::PrivateUser, Error> {
+        self.get_json("me".to_string(), &HashMap::new()).await
+    }
+
+    pub async fn playlist_snapshot_id(&self, playlist_id: String) -> Result<String, Error> {
+        #[derive(serde::Deserialize)]
+        struct Snapshot {
+            snapshot_id: String,
+        }
+        let params = HashMap::<&str, Value>::from_iter([("fields", "snapshot_id".into())]);
+        let snapshot: Snapshot = self
+            .get_json(format!("playlists/{}", playlist_id), &params)
+            .await?;
+        Ok(snapshot.snapshot_id)
+    }
+
+    pub async fn saved_tracks_page(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<rspotify_model::Page<rspotify_model::SavedTrack>, Error> {
+        let params = HashMap::<&str, Value>::from_iter(
+            vec![
+                limit.map(|limit| ("limit", limit.into())),
+                offset.map(|offset| ("offset", offset.into())),
+            ]
+            .into_iter()
+            .filter_map(|e| e),
+        );
+        self.get_json("me/tracks".to_string(), &params).await
+    }
+
+    pub async fn followed_artists_page(
+        &self,
+        limit: Option<u32>,
+        after: Option<String>,
+    ) -> Result<rspotify_model::CursorBasedPage<rspotify_model::FullArtist>, Error> {
+        let params = HashMap::<&str, Value>::from_iter(
+            vec![
+                Some(("type", "artist".into())),
+                limit.map(|limit| ("limit", limit.into())),
+                after.map(|after| ("after", after.into())),
+            ]
+            .into_iter()
+            .filter_map(|e| e),
+        );
+        let page: rspotify_model::CursorPageFullArtists =
+            self.get_json("me/following".to_string(), &params).await?;
+        Ok(page.artists)
+    }
+
+    pub async fn saved_albums_page(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<rspotify_model::Page<rspotify_model::SavedAlbum>, Error> {
+        let params = HashMap::<&str, Value>::from_iter(
+            vec![
+                limit.map(|limit| ("limit", limit.into())),
+                offset.map(|offset| ("offset", offset.into())),
+            ]
+            .into_iter()
+            .filter_map(|e| e),
+        );
+        self.get_json("me/albums".to_string(), &params).await
+    }
+
+    pub async fn album(&self, album_id: String) -> Result<model::spotify::FullAlbum, Error> {
+        let album_id = rspotify_model::AlbumId::from_id(&album_id).map_err(ApiError::from)?;
+        self.get_json(format!("albums/{}", album_id.id()), &HashMap::new())
+            .await
+    }
+
+    pub async fn album_tracks_page(
+        &self,
+        album_id: String,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<rspotify_model::Page<rspotify_model::SimplifiedTrack>, Error> {
+        let album_id = rspotify_model::AlbumId::from_id(&album_id).map_err(ApiError::from)?;
+        let params = HashMap::<&str, Value>::from_iter(
+            vec![
+                limit.map(|limit| ("limit", limit.into())),
+                offset.map(|offset| ("offset", offset.into())),
+            ]
+            .into_iter()
+            .filter_map(|e| e),
+        );
+        self.get_json(format!("albums/{}/tracks", album_id.id()), &params)
+            .await
+    }
+
+    pub async fn artist_albums_page(
+        &self,
+        artist_id: String,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<rspotify_model::Page<rspotify_model::SimplifiedAlbum>, Error> {
+        let artist_id = rspotify_model::ArtistId::from_id(&artist_id).map_err(ApiError::from)?;
+        let params = HashMap::<&str, Value>::from_iter(
+            vec![
+                // albums the artist only appears on are not part of the discography
+                Some(("include_groups", "album,single,compilation".into())),
+                limit.map(|limit| ("limit", limit.into())),
+                offset.map(|offset| ("offset", offset.into())),
+            ]
+            .into_iter()
+            .filter_map(|e| e),
+        );
+        self.get_json(format!("artists/{}/albums", artist_id.id()), &params)
+            .await
+    }
+
+    pub async fn top_tracks(
+        &self,
+        artist_id: String,
+    ) -> Result<Vec<rspotify_model::FullTrack>, Error> {
+        let artist_id = rspotify_model::ArtistId::from_id(&artist_id).map_err(ApiError::from)?;
+        // the market of the current user
+        let params = HashMap::<&str, Value>::from_iter([("market", "from_token".into())]);
+        let tracks: rspotify_model::FullTracks = self
+            .get_json(format!("artists/{}/top-tracks", artist_id.id()), &params)
+            .await?;
+        Ok(tracks.tracks)
+    }
+
     // pub async fn playlist_items(
     //     &self,
     //     playlist_id: &PlaylistId,
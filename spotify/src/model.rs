@@ -41,6 +41,7 @@ pub mod spotify {
                 id: Some(id),
                 total: playlist.tracks.total,
                 name: playlist.name,
+                version: playlist.snapshot_id,
                 tracks: Vec::new(),
             }
         }
@@ -93,15 +94,7 @@ pub mod spotify {
                         id: ep.id.to_string(), // episodes always have an ID
                         playlist_id: None,     // unknown at this point
                     };
-                    let mut images = ep
-                        .show
-                        .images
-                        .into_iter()
-                        .map(Image)
-                        .map(model::Artwork::from)
-                        .collect::<Vec<model::Artwork>>();
-                    images.sort_by(|b, a| (a.width * a.height).cmp(&(b.width * b.height)));
-                    let artwork = images.first().map(|a| a.to_owned());
+                    let artwork = sorted_artwork(ep.show.images).into_iter().next();
 
                     let preview = ep.audio_preview_url.map(|url| model::TrackPreview { url });
                     Ok(model::Track {
@@ -110,8 +103,13 @@ pub mod spotify {
                         artwork,
                         preview,
                         name: ep.name,
+                        artists: vec![model::Artist {
+                            id: None, // publishers have no ID
+                            name: ep.show.publisher.to_owned(),
+                        }],
                         artist: ep.show.publisher,
-                        info: None,
+                        explicit: ep.explicit,
+                        ..Default::default()
                     })
                 }
                 _ => Err(Error::Api(ApiError::InvalidMediaType)),
@@ -130,15 +128,168 @@ pub mod spotify {
                 }),
                 total: playlist.tracks.total,
                 name: playlist.name,
+                version: playlist.snapshot_id,
                 tracks: Vec::new(),
             }
         }
     }
 
+    wrap!(SimplifiedArtist);
+    impl From<SimplifiedArtist> for model::Artist {
+        fn from(artist: SimplifiedArtist) -> Self {
+            let artist = artist.into_inner();
+            model::Artist {
+                // artists of local tracks have no ID
+                id: artist.id.map(|id| model::ArtistId {
+                    source: model::Service::Spotify as i32,
+                    id: id.id().to_string(),
+                }),
+                name: artist.name,
+            }
+        }
+    }
+
+    wrap!(SimplifiedAlbum);
+    impl From<SimplifiedAlbum> for model::Album {
+        fn from(album: SimplifiedAlbum) -> Self {
+            let album = album.into_inner();
+            model::Album {
+                id: album.id.map(|id| model::AlbumId {
+                    source: model::Service::Spotify as i32,
+                    id: id.id().to_string(),
+                }),
+                name: album.name,
+                artists: album
+                    .artists
+                    .into_iter()
+                    .map(SimplifiedArtist)
+                    .map(model::Artist::from)
+                    .collect(),
+                album_type: album.album_type.unwrap_or_default(),
+                release_date: album.release_date.unwrap_or_default(),
+                // only known for full albums
+                total_tracks: 0,
+                label: String::new(),
+                genres: Vec::new(),
+            }
+        }
+    }
+
+    wrap!(FullAlbum);
+    impl From<FullAlbum> for model::Album {
+        fn from(album: FullAlbum) -> Self {
+            let album = album.into_inner();
+            let album_type: &'static str = album.album_type.into();
+            model::Album {
+                id: Some(model::AlbumId {
+                    source: model::Service::Spotify as i32,
+                    id: album.id.id().to_string(),
+                }),
+                name: album.name,
+                artists: album
+                    .artists
+                    .into_iter()
+                    .map(SimplifiedArtist)
+                    .map(model::Artist::from)
+                    .collect(),
+                album_type: album_type.to_string(),
+                release_date: album.release_date,
+                total_tracks: album.tracks.total,
+                label: album.label.unwrap_or_default(),
+                genres: album.genres,
+            }
+        }
+    }
+
+    /// Largest image first
+    pub fn sorted_artwork(images: Vec<rspotify_model::Image>) -> Vec<model::Artwork> {
+        let mut images: Vec<model::Artwork> = images
+            .into_iter()
+            .map(Image)
+            .map(model::Artwork::from)
+            .collect();
+        images.sort_by(|b, a| (a.width * a.height).cmp(&(b.width * b.height)));
+        images
+    }
+
+    wrap!(PrivateUser);
+    impl From<PrivateUser> for model::User {
+        fn from(user: PrivateUser) -> Self {
+            let user = user.into_inner();
+            model::User {
+                id: Some(model::UserId {
+                    source: model::Service::Spotify as i32,
+                    id: user.id.id().to_string(),
+                }),
+                display_name: user.display_name.unwrap_or_default(),
+                avatar: sorted_artwork(user.images.unwrap_or_default())
+                    .into_iter()
+                    .next(),
+            }
+        }
+    }
+
+    wrap!(FullArtist);
+    impl From<FullArtist> for model::Artist {
+        fn from(artist: FullArtist) -> Self {
+            let artist = artist.into_inner();
+            model::Artist {
+                id: Some(model::ArtistId {
+                    source: model::Service::Spotify as i32,
+                    id: artist.id.id().to_string(),
+                }),
+                name: artist.name,
+            }
+        }
+    }
+
+    wrap!(SimplifiedTrack);
+    impl From<SimplifiedTrack> for model::Track {
+        /// Tracks of an album, which is not part of the simplified track
+        fn from(track: SimplifiedTrack) -> Self {
+            let track = track.into_inner();
+            let id = model::TrackId {
+                source: model::Service::Spotify as i32,
+                id: track
+                    .id
+                    .map(|id| id.id().to_string())
+                    .unwrap_or("unknown".to_string()),
+                playlist_id: None,
+            };
+            let artists: Vec<model::Artist> = track
+                .artists
+                .into_iter()
+                .map(SimplifiedArtist)
+                .map(model::Artist::from)
+                .collect();
+            let artist = artists
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ");
+            model::Track {
+                id: Some(id),
+                name: track.name,
+                duration_millis: track.duration.num_milliseconds() as u64,
+                preview: track.preview_url.map(|url| model::TrackPreview { url }),
+                artist,
+                artists,
+                track_number: track.track_number,
+                disc_number: track.disc_number.max(0) as u32,
+                explicit: track.explicit,
+                info: Some(model::track::Info::SpotifyTrack(model::SpotifyTrack {
+                    popularity: 0,
+                    is_local: track.is_local,
+                })),
+                ..Default::default()
+            }
+        }
+    }
+
     wrap!(FullTrack);
     impl From<FullTrack> for model::Track {
         fn from(track: FullTrack) -> Self {
-            let track = track.into_inner();
+            let mut track = track.into_inner();
             let id = model::TrackId {
                 source: model::Service::Spotify as i32,
                 // tracks dont need an ID if they are local
@@ -148,24 +299,28 @@ pub mod spotify {
                     .unwrap_or("unknown".to_string()),
                 playlist_id: None, // unknown at this point
             };
-            let mut images: Vec<model::Artwork> = track
-                .album
-                .images
+            let artwork = sorted_artwork(std::mem::take(&mut track.album.images))
                 .into_iter()
-                .map(Image)
-                .map(model::Artwork::from)
-                .collect();
-
-            images.sort_by(|b, a| (a.width * a.height).cmp(&(b.width * b.height)));
-            let artwork = images.first().map(|a| a.to_owned());
+                .next();
 
             let preview = track.preview_url.map(|url| model::TrackPreview { url });
-            let artist = track
+            let artists: Vec<model::Artist> = track
                 .artists
                 .into_iter()
-                .map(|a| a.name)
-                .collect::<Vec<String>>()
+                .map(SimplifiedArtist)
+                .map(model::Artist::from)
+                .collect();
+            let artist = artists
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<&str>>()
                 .join(", ");
+            let mut external_ids = track.external_ids;
+            let external_ids = model::ExternalIds {
+                isrc: external_ids.remove("isrc").unwrap_or_default(),
+                ean: external_ids.remove("ean").unwrap_or_default(),
+                upc: external_ids.remove("upc").unwrap_or_default(),
+            };
 
             model::Track {
                 id: Some(id),
@@ -174,7 +329,18 @@ pub mod spotify {
                 artwork,
                 preview,
                 artist,
-                info: None,
+                artists,
+                album: Some(SimplifiedAlbum(track.album).into()),
+                track_number: track.track_number,
+                disc_number: track.disc_number.max(0) as u32,
+                explicit: track.explicit,
+                external_ids: Some(external_ids),
+                // genres are only known for artists and full albums
+                genres: Vec::new(),
+                info: Some(model::track::Info::SpotifyTrack(model::SpotifyTrack {
+                    popularity: track.popularity,
+                    is_local: track.is_local,
+                })),
             }
         }
     }
@@ -1,5 +1,6 @@
 use crate::error::{ApiError, AuthError, Error};
-use crate::{model, stream::paginate};
+use crate::model;
+use crate::stream::{paginate, paginate_with_cursor, paginate_with_progress};
 use djtool_model::source;
 use futures::{StreamExt, TryStreamExt};
 use std::sync::Arc;
@@ -12,6 +13,30 @@ impl source::Source for crate::Spotify {
         model::Service::Spotify
     }
 
+    fn capabilities(&self) -> model::Capabilities {
+        model::Capabilities {
+            search: true,
+            playlists: true,
+            user_library: true,
+            previews: true,
+            downloads: false,
+            requires_login: true,
+            // spotify limits requests within a rolling 30 second window,
+            // but does not document how many
+            rate_limit: Some(model::RateLimit {
+                requests: None,
+                window_secs: 30,
+            }),
+        }
+    }
+
+    async fn current_user(&self) -> Result<model::User, source::Error> {
+        self.me()
+            .await
+            .map(model::User::from)
+            .map_err(source::Error::from)
+    }
+
     // async fn reauthenticate(&self) -> Result<Option<reqwest::Url>, source::Error> {
     //     match self.authenticator.reauthenticate().await {
     //         Err(Error::Auth(AuthError::RequireUserLogin { auth_url })) => Ok(Some(auth_url)),
@@ -24,7 +49,7 @@ impl source::Source for crate::Spotify {
         let url = crate::api!(format!("playlists/{}", id))
             .map_err(ApiError::from)
             .map_err(Error::from)
-            .map_err(|err| source::Error::Custom(err.into()))?;
+            .map_err(source::Error::from)?;
         let res = self
             .client
             .get(url)
@@ -33,7 +58,7 @@ impl source::Source for crate::Spotify {
             .await
             .map_err(ApiError::from)
             .map_err(Error::from)
-            .map_err(|err| source::Error::Custom(err.into()))?;
+            .map_err(source::Error::from)?;
         // println!("playlist by id: {:?}", res);
         match res.status() {
             reqwest::StatusCode::OK => {
@@ -42,7 +67,7 @@ impl source::Source for crate::Spotify {
                     .await
                     .map_err(ApiError::from)
                     .map_err(Error::from)
-                    .map_err(|err| source::Error::Custom(err.into()))?;
+                    .map_err(source::Error::from)?;
                 Ok(Some(playlist.into()))
             }
             reqwest::StatusCode::BAD_REQUEST => Ok(None),
@@ -51,7 +76,7 @@ impl source::Source for crate::Spotify {
                 .map(|_| None)
                 .map_err(ApiError::from)
                 .map_err(Error::from)
-                .map_err(|err| source::Error::Custom(err.into())),
+                .map_err(source::Error::from),
         }
         // println!("response: {:?}", res.json::<serde_json::Value>().await);
     }
@@ -60,7 +85,7 @@ impl source::Source for crate::Spotify {
         let url = crate::api!(format!("tracks/{}", id))
             .map_err(ApiError::from)
             .map_err(Error::from)
-            .map_err(|err| source::Error::Custom(err.into()))?;
+            .map_err(source::Error::from)?;
 
         let res = self
             .client
@@ -70,7 +95,7 @@ impl source::Source for crate::Spotify {
             .await
             .map_err(ApiError::from)
             .map_err(Error::from)
-            .map_err(|err| source::Error::Custom(err.into()))?;
+            .map_err(source::Error::from)?;
 
         match res.status() {
             reqwest::StatusCode::OK => {
@@ -78,7 +103,7 @@ impl source::Source for crate::Spotify {
                     .json::<model::spotify::FullTrack>()
                     .await
                     .map_err(ApiError::from)
-                    .map_err(|err| source::Error::Custom(err.into()))?;
+                    .map_err(source::Error::from)?;
 
                 Ok(Some(track.into()))
             }
@@ -88,11 +113,21 @@ impl source::Source for crate::Spotify {
                 .map(|_| None)
                 .map_err(ApiError::from)
                 .map_err(Error::from)
-                .map_err(|err| source::Error::Custom(err.into())),
+                .map_err(source::Error::from),
         }
         // println!("response: {:?}", res.json::<serde_json::Value>().await);
     }
 
+    async fn playlist_version(
+        &self,
+        id: &model::PlaylistId,
+    ) -> Result<Option<String>, source::Error> {
+        self.playlist_snapshot_id(id.id.to_owned())
+            .await
+            .map(Some)
+            .map_err(source::Error::from)
+    }
+
     async fn search(
         &self,
         query: source::SearchQuery,
@@ -109,7 +144,8 @@ impl source::Source for crate::Spotify {
         progress: Box<dyn Fn(source::QueryProgress) -> () + Send + 'static>,
         limit: Option<usize>,
     ) -> source::SearchResultStream<model::Track> {
-        let search_results = paginate(
+        let query_string = query.to_string();
+        let search_results = paginate_with_progress(
             move |limit, offset| {
                 let query = query.clone();
                 async move {
@@ -120,15 +156,17 @@ impl source::Source for crate::Spotify {
                             // or from the source?
                             rspotify_model::SearchType::Track,
                         )),
-                        Err(err) => Err(Error::Unknown(err.into())),
+                        Err(err) => Err(err),
                     }
                 }
             },
             DEFAULT_PAGINATION_CHUNKS,
+            query_string,
+            progress,
         );
         let tracks = search_results
             .map(|track| track.map(|t| model::spotify::FullTrack(t).into()))
-            .map_err(|err| source::Error::Custom(err.into()));
+            .map_err(source::Error::from);
 
         match limit {
             Some(limit) => Box::pin(tracks.take(limit)),
@@ -168,7 +206,7 @@ impl source::Source for crate::Spotify {
             .map(|playlist: Result<model::spotify::SimplifiedPlaylist, _>| {
                 playlist.map(|p| p.into())
             })
-            .map_err(|err| source::Error::Custom(err.into()));
+            .map_err(source::Error::from);
         Ok(Box::pin(playlists))
     }
 
@@ -207,11 +245,119 @@ impl source::Source for crate::Spotify {
                         t
                     })
             })
-            .map_err(|err| source::Error::Custom(err.into()));
+            .map_err(source::Error::from);
         // .map_err(source::Error::from);
         Ok(Box::pin(tracks))
     }
 
+    fn saved_tracks_stream<'a>(&'a self) -> Result<source::TrackStream<'a>, source::Error> {
+        let tracks = paginate(
+            move |limit, offset| self.saved_tracks_page(Some(limit), Some(offset)),
+            DEFAULT_PAGINATION_CHUNKS,
+        );
+        let tracks = tracks
+            .map_ok(|saved| model::spotify::FullTrack(saved.track).into())
+            .map_err(source::Error::from);
+        Ok(Box::pin(tracks))
+    }
+
+    fn followed_artists_stream<'a>(&'a self) -> Result<source::ArtistStream<'a>, source::Error> {
+        let artists = paginate_with_cursor(
+            move |limit, after| self.followed_artists_page(Some(limit), after),
+            DEFAULT_PAGINATION_CHUNKS,
+        );
+        let artists = artists
+            .map_ok(|artist| model::spotify::FullArtist(artist).into())
+            .map_err(source::Error::from);
+        Ok(Box::pin(artists))
+    }
+
+    fn saved_albums_stream<'a>(&'a self) -> Result<source::AlbumStream<'a>, source::Error> {
+        let albums = paginate(
+            move |limit, offset| self.saved_albums_page(Some(limit), Some(offset)),
+            DEFAULT_PAGINATION_CHUNKS,
+        );
+        let albums = albums
+            .map_ok(|saved| model::spotify::FullAlbum(saved.album).into())
+            .map_err(source::Error::from);
+        Ok(Box::pin(albums))
+    }
+
+    fn album_tracks_stream<'a>(
+        &'a self,
+        album_id: model::AlbumId,
+    ) -> Result<source::TrackStream<'a>, source::Error> {
+        let tracks = async_stream::try_stream! {
+            // simplified tracks lack the album and its artwork
+            let mut album = self.album(album_id.id.to_owned()).await?.into_inner();
+            let artwork = model::spotify::sorted_artwork(album.images.to_owned())
+                .into_iter()
+                .next();
+            // the full album already contains the first page of its tracks
+            let first_page = std::mem::take(&mut album.tracks.items);
+            let fetched = first_page.len() as u32;
+            let more = album.tracks.next.is_some();
+            let album: model::Album = model::spotify::FullAlbum(album).into();
+            let with_album = |track| {
+                let mut track: model::Track = model::spotify::SimplifiedTrack(track).into();
+                track.album = Some(album.to_owned());
+                track.artwork = artwork.to_owned();
+                track
+            };
+
+            for track in first_page {
+                yield with_album(track);
+            }
+            if more {
+                let tracks = paginate(
+                    move |limit, offset| {
+                        self.album_tracks_page(
+                            album_id.id.to_owned(),
+                            Some(limit),
+                            Some(fetched + offset),
+                        )
+                    },
+                    DEFAULT_PAGINATION_CHUNKS,
+                );
+                for await track in tracks {
+                    yield with_album(track?);
+                }
+            }
+        };
+        let tracks = tracks.map_err(|err: Error| source::Error::from(err));
+        Ok(Box::pin(tracks))
+    }
+
+    fn artist_albums_stream<'a>(
+        &'a self,
+        artist_id: model::ArtistId,
+    ) -> Result<source::AlbumStream<'a>, source::Error> {
+        let albums = paginate(
+            move |limit, offset| {
+                self.artist_albums_page(artist_id.id.to_owned(), Some(limit), Some(offset))
+            },
+            DEFAULT_PAGINATION_CHUNKS,
+        );
+        let albums = albums
+            .map_ok(|album| model::spotify::SimplifiedAlbum(album).into())
+            .map_err(source::Error::from);
+        Ok(Box::pin(albums))
+    }
+
+    async fn artist_top_tracks(
+        &self,
+        artist_id: &model::ArtistId,
+    ) -> Result<Vec<model::Track>, source::Error> {
+        let tracks = self
+            .top_tracks(artist_id.id.to_owned())
+            .await
+            .map_err(source::Error::from)?;
+        Ok(tracks
+            .into_iter()
+            .map(|track| model::spotify::FullTrack(track).into())
+            .collect())
+    }
+
     async fn handle_user_login_callback(
         &self,
         login: model::UserLoginCallback,
@@ -223,7 +369,7 @@ impl source::Source for crate::Spotify {
                 .authenticator
                 .handle_user_login_callback(login_test)
                 .await
-                .map_err(|err| source::Error::Custom(err.into())),
+                .map_err(source::Error::from),
             // .map_err(source::Error::from),
             _ => panic!("wrong login callback received"),
         }
@@ -1,12 +1,30 @@
+use djtool_model::source::QueryProgress;
 use futures::stream::Stream;
 use futures::Future;
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
+use std::time::Instant;
 
 pub fn paginate<'a, T: 'a, E: 'a, Fut, Req: 'a>(
     req: Req,
     page_size: u32,
 ) -> impl Stream<Item = Result<T, E>> + 'a + Send
+where
+    T: Unpin + Send,
+    E: Send,
+    Fut: Future<Output = Result<rspotify_model::Page<T>, E>> + Send,
+    Req: Fn(u32, u32) -> Fut + Send + Sync,
+{
+    paginate_with_progress(req, page_size, String::new(), Box::new(|_| {}))
+}
+
+/// Like `paginate`, but reports the progress of `query` after every fetched page
+pub fn paginate_with_progress<'a, T: 'a, E: 'a, Fut, Req: 'a>(
+    req: Req,
+    page_size: u32,
+    query: String,
+    progress: Box<dyn Fn(QueryProgress) -> () + Send + 'static>,
+) -> impl Stream<Item = Result<T, E>> + 'a + Send
 where
     T: Unpin + Send,
     E: Send,
@@ -14,10 +32,20 @@ where
     Req: Fn(u32, u32) -> Fut + Send + Sync,
 {
     let mut offset = 0;
+    let started = Instant::now();
+    let mut state = QueryProgress {
+        query,
+        ..QueryProgress::default()
+    };
     Box::pin(async_stream::stream! {
         loop {
             let page = req(page_size, offset).await?;
             offset += page.items.len() as u32;
+            state.pages += 1;
+            state.items += page.items.len();
+            state.total = Some(page.total as usize);
+            state.elapsed = started.elapsed();
+            progress(state.clone());
             for item in page.items {
                 yield Ok(item);
             }
@@ -27,3 +55,80 @@ where
         }
     })
 }
+
+/// Like `paginate`, but for endpoints that page with an `after` cursor instead of an offset
+pub fn paginate_with_cursor<'a, T: 'a, E: 'a, Fut, Req: 'a>(
+    req: Req,
+    page_size: u32,
+) -> impl Stream<Item = Result<T, E>> + 'a + Send
+where
+    T: Unpin + Send,
+    E: Send,
+    Fut: Future<Output = Result<rspotify_model::CursorBasedPage<T>, E>> + Send,
+    Req: Fn(u32, Option<String>) -> Fut + Send + Sync,
+{
+    let mut after = None;
+    Box::pin(async_stream::stream! {
+        loop {
+            let page = req(page_size, after.take()).await?;
+            for item in page.items {
+                yield Ok(item);
+            }
+            after = page.cursors.and_then(|cursors| cursors.after);
+            if page.next.is_none() || after.is_none() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::paginate_with_progress;
+    use djtool_model::source::QueryProgress;
+    use futures::TryStreamExt;
+    use std::sync::{Arc, Mutex};
+
+    /// Page of the numbers up to `total`, starting at `offset`
+    fn page(limit: u32, offset: u32, total: u32) -> rspotify_model::Page<u32> {
+        let end = (offset + limit).min(total);
+        rspotify_model::Page {
+            href: String::new(),
+            items: (offset..end).collect(),
+            limit,
+            next: (end < total).then(|| format!("?offset={end}")),
+            offset,
+            previous: None,
+            total,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paginate_with_progress() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let progress = {
+            let reports = reports.clone();
+            Box::new(move |state: QueryProgress| reports.lock().unwrap().push(state))
+        };
+        let items: Vec<u32> = paginate_with_progress(
+            |limit, offset| async move { Ok::<_, ()>(page(limit, offset, 5)) },
+            2,
+            "query".to_string(),
+            progress,
+        )
+        .try_collect()
+        .await
+        .unwrap();
+        assert_eq!(items, vec![0, 1, 2, 3, 4]);
+
+        let reports = reports.lock().unwrap();
+        let counts: Vec<(usize, usize)> = reports
+            .iter()
+            .map(|state| (state.pages, state.items))
+            .collect();
+        assert_eq!(counts, vec![(1, 2), (2, 4), (3, 5)]);
+        assert!(reports
+            .iter()
+            .all(|state| state.query == "query" && state.total == Some(5)));
+    }
+}
@@ -1,5 +1,8 @@
 #![allow(warnings)]
 
+pub mod progress;
+pub mod sync;
+
 use djtool_model::{self as model, source};
 use library::Library;
 use std::collections::HashMap;
@@ -34,7 +37,18 @@ impl std::fmt::Display for DjTool {
 }
 
 #[derive(thiserror::Error, Debug)]
-pub enum Error {}
+pub enum Error {
+    #[error("source error: {0}")]
+    Source(#[from] source::Error),
+    #[error("library error: {0}")]
+    Library(#[from] library::Error),
+    #[error("playlist has no ID")]
+    MissingPlaylistId,
+    #[error("{0}")]
+    Track(#[from] model::TrackError),
+    #[error("no source for playlist {0}")]
+    NoSource(model::PlaylistId),
+}
 
 impl DjTool {
     pub fn new(
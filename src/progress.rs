@@ -1,45 +1,198 @@
+use djtool_model as model;
+use model::sync_progress_update::Update;
+use model::track_stage_progress::Progress;
 
-struct SyncProgress {
-    playlists: i64,
-    tracks: i64,
-    playlists_succeeded: i64,
-    playlists_failed: i64,
-    tracks_succeeded: i64,
-    tracks_failed: i64,
-    tracks_in_progress: i64,
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyncProgress {
+    pub playlists: u64,
+    pub tracks: u64,
+    pub playlists_succeeded: u64,
+    pub playlists_failed: u64,
+    pub tracks_succeeded: u64,
+    pub tracks_failed: u64,
+    pub tracks_in_progress: u64,
 }
 
-enum TranscodeProgress {
-    HighQuality { done_percent: i64 },
-    LowQuality { done_percent: i64 },
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TranscodeProgress {
+    HighQuality { done_percent: f32 },
+    LowQuality { done_percent: f32 },
 }
 
-enum PlaylistCompletion {
-    Succeeded {
-        succeeded: u64,
-        failed: u64,
-    },
-    Failed,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaylistCompletion {
+    Succeeded { succeeded: u64, failed: u64 },
+    Failed { reason: String },
 }
 
-enum PlaylistProgress {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaylistProgress {
     Fetch {
         failed: u64,
         fetched: u64,
         in_progress: u64,
+        total: Option<u64>,
     },
     Completed(PlaylistCompletion),
 }
 
-enum TrackCompletion {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrackCompletion {
     Succeeded,
-    Failed,
+    Failed { reason: String },
 }
 
-enum TrackProgress {
-    DownloadPreview { downloaded: i64, total: i64 },
-    DownloadTrack { downloaded: i64, total: i64 },
-    DownloadArtwork { downloaded: i64, total: i64 },
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackProgress {
+    Match,
+    DownloadPreview { downloaded: u64, total: Option<u64> },
+    DownloadTrack { downloaded: u64, total: Option<u64> },
+    DownloadArtwork { downloaded: u64, total: Option<u64> },
     Transcode(TranscodeProgress),
+    Tag,
+    Move,
     Completed(TrackCompletion),
 }
+
+impl From<SyncProgress> for model::SyncProgressUpdate {
+    fn from(progress: SyncProgress) -> Self {
+        Self {
+            update: Some(Update::Counters(model::SyncCounters {
+                playlists: progress.playlists,
+                playlists_succeeded: progress.playlists_succeeded,
+                playlists_failed: progress.playlists_failed,
+                tracks: progress.tracks,
+                tracks_succeeded: progress.tracks_succeeded,
+                tracks_failed: progress.tracks_failed,
+                tracks_in_progress: progress.tracks_in_progress,
+            })),
+        }
+    }
+}
+
+impl PlaylistProgress {
+    pub fn into_update(self, id: model::PlaylistId) -> model::SyncProgressUpdate {
+        let id = Some(id);
+        let update = match self {
+            PlaylistProgress::Fetch {
+                failed,
+                fetched,
+                in_progress,
+                total,
+            } => Update::PlaylistFetch(model::PlaylistFetchProgress {
+                id,
+                fetched,
+                failed,
+                in_progress,
+                total: total.unwrap_or(0),
+            }),
+            PlaylistProgress::Completed(PlaylistCompletion::Succeeded { succeeded, failed }) => {
+                Update::PlaylistCompleted(model::PlaylistCompleted {
+                    id,
+                    succeeded,
+                    failed,
+                })
+            }
+            PlaylistProgress::Completed(PlaylistCompletion::Failed { reason }) => {
+                Update::PlaylistFailed(model::PlaylistFailed { id, reason })
+            }
+        };
+        model::SyncProgressUpdate {
+            update: Some(update),
+        }
+    }
+}
+
+impl TrackProgress {
+    pub fn into_update(self, id: model::TrackId) -> model::SyncProgressUpdate {
+        use model::TrackStage;
+        let id = Some(id);
+        let bytes = |downloaded, total: Option<u64>| {
+            Some(Progress::Bytes(model::ByteProgress {
+                downloaded,
+                total: total.unwrap_or(0),
+            }))
+        };
+        let (stage, progress) = match self {
+            TrackProgress::Match => (TrackStage::Match, None),
+            TrackProgress::DownloadPreview { downloaded, total } => {
+                (TrackStage::DownloadPreview, bytes(downloaded, total))
+            }
+            TrackProgress::DownloadTrack { downloaded, total } => {
+                (TrackStage::DownloadTrack, bytes(downloaded, total))
+            }
+            TrackProgress::DownloadArtwork { downloaded, total } => {
+                (TrackStage::DownloadArtwork, bytes(downloaded, total))
+            }
+            TrackProgress::Transcode(TranscodeProgress::HighQuality { done_percent }) => (
+                TrackStage::TranscodeHighQuality,
+                Some(Progress::Percent(done_percent)),
+            ),
+            TrackProgress::Transcode(TranscodeProgress::LowQuality { done_percent }) => (
+                TrackStage::TranscodeLowQuality,
+                Some(Progress::Percent(done_percent)),
+            ),
+            TrackProgress::Tag => (TrackStage::Tag, None),
+            TrackProgress::Move => (TrackStage::Move, None),
+            TrackProgress::Completed(TrackCompletion::Succeeded) => {
+                return model::SyncProgressUpdate {
+                    update: Some(Update::TrackCompleted(model::TrackCompleted { id })),
+                };
+            }
+            TrackProgress::Completed(TrackCompletion::Failed { reason }) => {
+                return model::SyncProgressUpdate {
+                    update: Some(Update::TrackFailed(model::TrackFailed { id, reason })),
+                };
+            }
+        };
+        model::SyncProgressUpdate {
+            update: Some(Update::TrackStage(model::TrackStageProgress {
+                id,
+                stage: stage as i32,
+                progress,
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_progress_update() {
+        let id = model::TrackId {
+            id: "id".to_string(),
+            source: model::Service::Spotify as i32,
+            playlist_id: None,
+        };
+        let update = TrackProgress::DownloadTrack {
+            downloaded: 10,
+            total: Some(100),
+        }
+        .into_update(id.clone());
+        assert_eq!(
+            update.update,
+            Some(Update::TrackStage(model::TrackStageProgress {
+                id: Some(id.clone()),
+                stage: model::TrackStage::DownloadTrack as i32,
+                progress: Some(Progress::Bytes(model::ByteProgress {
+                    downloaded: 10,
+                    total: 100,
+                })),
+            }))
+        );
+
+        let update = TrackProgress::Completed(TrackCompletion::Failed {
+            reason: "no match".to_string(),
+        })
+        .into_update(id.clone());
+        assert_eq!(
+            update.update,
+            Some(Update::TrackFailed(model::TrackFailed {
+                id: Some(id),
+                reason: "no match".to_string(),
+            }))
+        );
+    }
+}
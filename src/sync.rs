@@ -0,0 +1,451 @@
+use crate::{DjTool, Error};
+use djtool_model as model;
+use djtool_model::sink::{Method, Sink};
+use futures::TryStreamExt;
+
+/// Changes of a playlist since its last sync
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistChanges {
+    pub diff: model::PlaylistDiff,
+    /// To be saved with `Library::save_playlist_snapshot` once the changes are synced
+    pub snapshot: model::PlaylistSnapshot,
+}
+
+impl DjTool {
+    /// Tracks added to and removed from a playlist since its last sync
+    ///
+    /// Returns `None` without fetching any tracks if the playlist version is unchanged.
+    /// All tracks of a playlist that was never synced are reported as added.
+    pub async fn playlist_changes(
+        &self,
+        playlist: model::Playlist,
+    ) -> Result<Option<PlaylistChanges>, Error> {
+        let id = playlist.id.to_owned().ok_or(Error::MissingPlaylistId)?;
+        let sources = self.sources.read().await;
+        let source = model::Service::from_i32(id.source)
+            .and_then(|service| sources.get(&service))
+            .ok_or_else(|| Error::NoSource(id.to_owned()))?;
+
+        let version = if playlist.version.is_empty() {
+            source.playlist_version(&id).await?.unwrap_or_default()
+        } else {
+            playlist.version.to_owned()
+        };
+        let previous = self.library.load_playlist_snapshot(&id).await?;
+        if let Some(ref previous) = previous {
+            if previous.is_current(&version) {
+                return Ok(None);
+            }
+        }
+
+        let tracks: Vec<model::Track> = source
+            .user_playlist_tracks_stream(playlist)?
+            .try_collect()
+            .await?;
+        let diff = match previous {
+            Some(previous) => previous.diff(&tracks),
+            None => model::PlaylistDiff {
+                added: tracks.to_owned(),
+                removed: Vec::new(),
+            },
+        };
+        let snapshot = model::PlaylistSnapshot::new(id, version, &tracks);
+        Ok(Some(PlaylistChanges { diff, snapshot }))
+    }
+
+    /// Resolves a source track to a track of the sink
+    ///
+    /// A link stored by a previous sync is reused without searching the sink again.
+    /// Otherwise the candidates of the sink are scored with `Track::match_score`.
+    /// `Method::Best` links the highest scoring candidate, the other methods the first
+    /// candidate ranked by the sink. The link is stored with the other candidates as
+    /// alternatives, best first. Returns `None` without storing a link if the sink
+    /// has no candidates or the linked one scores below `min_confidence`.
+    pub async fn link_track(
+        &self,
+        track: &model::Track,
+        sink: &dyn Sink,
+        method: &Method,
+    ) -> Result<Option<model::TrackLink>, Error> {
+        let source_id = track.id()?;
+        if let Some(link) = self.library.load_link(source_id).await? {
+            return Ok(Some(link));
+        }
+
+        let (limit, min_confidence) = match method {
+            Method::Best {
+                max_candidates,
+                min_confidence,
+            }
+            | Method::Fast {
+                max_candidates,
+                min_confidence,
+            } => (max_candidates.map(|max| max as usize), *min_confidence),
+            Method::First => (Some(1), None),
+        };
+        // sinks rank their candidates without reporting a score
+        let mut candidates: Vec<(model::TrackId, f32)> = sink
+            .candidates(track, Box::new(|_| {}), limit)
+            .await
+            .into_iter()
+            .filter_map(|candidate| {
+                let score = track.match_score(&candidate);
+                candidate.id.map(|id| (id, score))
+            })
+            .collect();
+        let by_score = |a: &(model::TrackId, f32), b: &(model::TrackId, f32)| b.1.total_cmp(&a.1);
+        if let Method::Best { .. } = method {
+            candidates.sort_by(by_score);
+        }
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+        let (sink_id, score) = candidates.remove(0);
+        if score < min_confidence.unwrap_or(0.0) {
+            return Ok(None);
+        }
+        candidates.sort_by(by_score);
+
+        let mut link = model::TrackLink::new(
+            source_id.to_owned(),
+            sink_id,
+            score,
+            model::MatchMethod::from(method),
+        );
+        link.alternatives = candidates
+            .into_iter()
+            .map(|(id, score)| model::TrackCandidate {
+                id: Some(id),
+                score,
+            })
+            .collect();
+        self.library.save_link(&link).await?;
+        Ok(Some(link))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DjTool, DynSource};
+    use djtool_model::sink::{
+        DownloadProgress, DownloadedTrack, Error, Method, QueryProgress, Sink,
+    };
+    use djtool_model::source::{self, SearchQuery, Source};
+    use djtool_model::{self as model, Service};
+    use futures::stream::{self, Stream};
+    use library::Library;
+    use std::path::Path;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn track(id: &str, source: Service) -> model::Track {
+        model::Track {
+            id: Some(model::TrackId {
+                id: id.to_string(),
+                source: source as i32,
+                playlist_id: None,
+            }),
+            name: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Source of a single playlist that counts how often its tracks are fetched
+    struct StubSource {
+        version: String,
+        tracks: Vec<model::Track>,
+        fetches: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Source for StubSource {
+        fn id(&self) -> Service {
+            Service::Spotify
+        }
+
+        fn capabilities(&self) -> model::Capabilities {
+            model::Capabilities::default()
+        }
+
+        async fn current_user(&self) -> Result<model::User, Error> {
+            unimplemented!()
+        }
+
+        async fn playlist_by_id(&self, _id: &String) -> Result<Option<model::Playlist>, Error> {
+            unimplemented!()
+        }
+
+        async fn track_by_id(&self, _id: &String) -> Result<Option<model::Track>, Error> {
+            unimplemented!()
+        }
+
+        async fn playlist_version(&self, _id: &model::PlaylistId) -> Result<Option<String>, Error> {
+            Ok(Some(self.version.to_owned()))
+        }
+
+        async fn search(
+            &self,
+            _query: SearchQuery,
+            _progress: Box<dyn Fn(QueryProgress) + Send + 'static>,
+            _limit: Option<usize>,
+        ) -> Vec<Result<model::Track, Error>> {
+            unimplemented!()
+        }
+
+        fn user_playlists_stream<'a>(
+            &'a self,
+            _user_id: &'a String,
+        ) -> Result<source::PlaylistStream, Error> {
+            unimplemented!()
+        }
+
+        fn user_playlist_tracks_stream<'a>(
+            &'a self,
+            _playlist: model::Playlist,
+        ) -> Result<source::TrackStream, Error> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(Box::pin(stream::iter(
+                self.tracks.to_owned().into_iter().map(Ok),
+            )))
+        }
+
+        fn saved_tracks_stream<'a>(&'a self) -> Result<source::TrackStream<'a>, Error> {
+            unimplemented!()
+        }
+
+        fn followed_artists_stream<'a>(&'a self) -> Result<source::ArtistStream<'a>, Error> {
+            unimplemented!()
+        }
+
+        fn saved_albums_stream<'a>(&'a self) -> Result<source::AlbumStream<'a>, Error> {
+            unimplemented!()
+        }
+
+        fn album_tracks_stream<'a>(
+            &'a self,
+            _album_id: model::AlbumId,
+        ) -> Result<source::TrackStream<'a>, Error> {
+            unimplemented!()
+        }
+
+        fn artist_albums_stream<'a>(
+            &'a self,
+            _artist_id: model::ArtistId,
+        ) -> Result<source::AlbumStream<'a>, Error> {
+            unimplemented!()
+        }
+
+        async fn artist_top_tracks(
+            &self,
+            _artist_id: &model::ArtistId,
+        ) -> Result<Vec<model::Track>, Error> {
+            unimplemented!()
+        }
+
+        fn search_stream<'a>(
+            &'a self,
+            _query: SearchQuery,
+            _progress: Box<dyn Fn(QueryProgress) + Send + 'static>,
+            _limit: Option<usize>,
+        ) -> source::SearchResultStream<model::Track> {
+            unimplemented!()
+        }
+
+        async fn handle_user_login_callback(
+            &self,
+            _login: model::UserLoginCallback,
+        ) -> Result<(), Error> {
+            unimplemented!()
+        }
+    }
+
+    /// Sink that offers fixed candidates and counts the searches
+    #[derive(Default)]
+    struct StubSink {
+        candidates: Vec<model::Track>,
+        searches: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Sink for StubSink {
+        fn capabilities(&self) -> model::Capabilities {
+            model::Capabilities::default()
+        }
+
+        async fn audio_download_url(
+            &self,
+            _track: &model::Track,
+        ) -> Result<(String, String), Error> {
+            unimplemented!()
+        }
+
+        async fn download(
+            &self,
+            _track: &model::Track,
+            _output_path: &(dyn AsRef<Path> + Sync + Send),
+            _method: Option<Method>,
+            _progress: &(dyn Fn(DownloadProgress)),
+        ) -> Result<DownloadedTrack, Error> {
+            unimplemented!()
+        }
+
+        async fn candidates(
+            &self,
+            _track: &model::Track,
+            _progress: Box<dyn Fn(QueryProgress) + Send + 'static>,
+            limit: Option<usize>,
+        ) -> Vec<model::Track> {
+            self.searches.fetch_add(1, Ordering::SeqCst);
+            let limit = limit.unwrap_or(self.candidates.len());
+            self.candidates.iter().take(limit).cloned().collect()
+        }
+
+        fn candidates_stream<'b, 'a>(
+            &'a self,
+            _track: &'b model::Track,
+            _progress: Box<dyn Fn(QueryProgress) + Send + 'static>,
+            _limit: Option<usize>,
+        ) -> Pin<Box<dyn Stream<Item = model::Track> + Send + 'a>> {
+            Box::pin(stream::iter(self.candidates.to_owned()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_track_reuses_stored_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = DjTool::new(Library {
+            path: dir.path().to_owned(),
+        })
+        .unwrap();
+        let named = |id: &str, name: &str, source: Service| model::Track {
+            name: name.to_string(),
+            artist: "deadmau5".to_string(),
+            duration_millis: 210_000,
+            ..track(id, source)
+        };
+        let sink = StubSink {
+            candidates: vec![
+                named("UqDN0G9Tx9k", "Strobe (Live)", Service::Youtube),
+                named("tKi9Z-f6qX4", "Strobe - Radio Edit", Service::Youtube),
+            ],
+            ..Default::default()
+        };
+        let source = named(
+            "4uLU6hMCjMI75M1A2tKUQC",
+            "Strobe - Radio Edit",
+            Service::Spotify,
+        );
+        let method = Method::Best {
+            max_candidates: Some(5),
+            min_confidence: Some(0.8),
+        };
+
+        // the best scoring candidate is linked, not the first one
+        let link = tool
+            .link_track(&source, &sink, &method)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(link.source_id, source.id);
+        assert_eq!(link.sink_id, sink.candidates[1].id);
+        assert_eq!(link.score, 1.0);
+        assert_eq!(link.method, model::MatchMethod::Best as i32);
+        assert_eq!(link.alternatives.len(), 1);
+        assert_eq!(link.alternatives[0].id, sink.candidates[0].id);
+        assert!(link.alternatives[0].score < link.score);
+
+        // the stored decision is reused without searching again
+        let reused = tool
+            .link_track(&source, &sink, &Method::First)
+            .await
+            .unwrap();
+        assert_eq!(reused, Some(link));
+        assert_eq!(sink.searches.load(Ordering::SeqCst), 1);
+
+        // a candidate below the minimum confidence is neither linked nor stored
+        let unmatched = named(
+            "7ouMYWpwJ422jRcDASZB7P",
+            "Ghosts 'n' Stuff",
+            Service::Spotify,
+        );
+        let fast = Method::Fast {
+            max_candidates: None,
+            min_confidence: Some(0.8),
+        };
+        assert_eq!(
+            tool.link_track(&unmatched, &sink, &fast).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            tool.library
+                .load_link(unmatched.id().unwrap())
+                .await
+                .unwrap(),
+            None
+        );
+
+        let empty = StubSink::default();
+        assert_eq!(
+            tool.link_track(&unmatched, &empty, &Method::First)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_playlist_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = DjTool::new(Library {
+            path: dir.path().to_owned(),
+        })
+        .unwrap();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let tracks = vec![track("a", Service::Spotify), track("b", Service::Spotify)];
+        let source: DynSource = Arc::new(Box::new(StubSource {
+            version: "MTY4".to_string(),
+            tracks: tracks.to_owned(),
+            fetches: fetches.clone(),
+        }));
+        tool.sources.write().await.insert(Service::Spotify, source);
+        let playlist = model::Playlist {
+            id: Some(model::PlaylistId {
+                id: "37i9dQZF1DXcBWIGoYBM5M".to_string(),
+                source: Service::Spotify as i32,
+            }),
+            ..Default::default()
+        };
+
+        // all tracks of a playlist that was never synced are added
+        let changes = tool
+            .playlist_changes(playlist.to_owned())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(changes.diff.added, tracks);
+        assert!(changes.diff.removed.is_empty());
+        assert_eq!(changes.snapshot.version, "MTY4");
+        tool.library
+            .save_playlist_snapshot(&changes.snapshot)
+            .await
+            .unwrap();
+
+        // the tracks of an unchanged playlist are not fetched again
+        assert_eq!(
+            tool.playlist_changes(playlist.to_owned()).await.unwrap(),
+            None
+        );
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // a newer version of the playlist is diffed against the snapshot
+        let changed = model::Playlist {
+            version: "MTY5".to_string(),
+            ..playlist
+        };
+        let changes = tool.playlist_changes(changed).await.unwrap().unwrap();
+        assert_eq!(changes.diff, model::PlaylistDiff::default());
+        assert_eq!(changes.snapshot.version, "MTY5");
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+}
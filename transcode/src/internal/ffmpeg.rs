@@ -2,10 +2,13 @@
     clippy::cast_possible_wrap,
     clippy::cast_possible_truncation,
     clippy::cast_precision_loss,
-    clippy::cast_sign_loss,
+    clippy::cast_sign_loss
 )]
 
-use crate::{Codec, Error, ProgressHandlerFunc, TranscodeProgress, Transcoder, TranscoderOptions};
+use crate::{
+    Codec, Dither, Error, ProgressHandlerFunc, ResampleEngine, ResampleFilter, ResamplerOptions,
+    TranscodeProgress, Transcoder, TranscoderOptions,
+};
 use djtool_ffmpeg as ffmpeg;
 use std::path::Path;
 use std::time::{Duration, Instant};
@@ -15,16 +18,77 @@ impl From<Codec> for ffmpeg::codec::id::Id {
         match codec {
             Codec::MP3 => Self::MP3,
             Codec::PCM => Self::PCM_S16LE,
+            Codec::AIFF => Self::PCM_S16BE,
         }
     }
 }
 
+fn engine_option(engine: ResampleEngine) -> &'static str {
+    match engine {
+        ResampleEngine::Software => "swr",
+        ResampleEngine::SoundExchange => "soxr",
+    }
+}
+
+fn dither_option(dither: Dither) -> &'static str {
+    match dither {
+        Dither::None => "none",
+        Dither::Rectangular => "rectangular",
+        Dither::Triangular => "triangular",
+        Dither::TriangularHighPass => "triangular_hp",
+        Dither::NoiseShapingLipshitz => "lipshitz",
+        Dither::NoiseShapingFWeighted => "f_weighted",
+        Dither::NoiseShapingModifiedEWeighted => "modified_e_weighted",
+        Dither::NoiseShapingImprovedEWeighted => "improved_e_weighted",
+        Dither::NoiseShapingShibata => "shibata",
+        Dither::NoiseShapingLowShibata => "low_shibata",
+        Dither::NoiseShapingHighShibata => "high_shibata",
+    }
+}
+
+fn filter_option(filter: ResampleFilter) -> &'static str {
+    match filter {
+        ResampleFilter::Cubic => "cubic",
+        ResampleFilter::BlackmanNuttall => "blackman_nuttall",
+        ResampleFilter::Kaiser => "kaiser",
+    }
+}
+
+/// Builds an explicit `aresample` filter that converts to the encoder
+/// sample rate and format using the requested resampler settings.
+///
+/// Without it, the conversion is implicitly inserted by `abuffersink`
+/// with the default swr settings and without dithering.
+fn resample_filter_spec(
+    options: &ResamplerOptions,
+    sample_rate: i32,
+    sample_format: ffmpeg::format::Sample,
+) -> String {
+    let mut args = vec![
+        format!("osr={sample_rate}"),
+        format!("osf={}", sample_format.name()),
+        format!("resampler={}", engine_option(options.engine)),
+        format!("dither_method={}", dither_option(options.dither)),
+    ];
+    if let Some(precision) = options.precision {
+        args.push(format!("precision={precision}"));
+    }
+    if let Some(filter) = options.filter {
+        args.push(format!("filter_type={}", filter_option(filter)));
+    }
+    if let Some(filter_size) = options.filter_size {
+        args.push(format!("filter_size={filter_size}"));
+    }
+    format!("aresample={}", args.join(":"))
+}
+
 struct FFmpegTranscode<'a> {
     stream: usize,
     filter: ffmpeg::filter::Graph,
     decoder: ffmpeg::codec::decoder::Audio,
     encoder: ffmpeg::codec::encoder::Audio,
     in_time_base: ffmpeg::Rational,
+    encoder_time_base: ffmpeg::Rational,
     out_time_base: ffmpeg::Rational,
     duration: u64,
     total_frames: u64,
@@ -80,7 +144,10 @@ impl<'a> FFmpegTranscode<'a> {
             encoder.set_flags(ffmpeg::codec::flag::Flags::GLOBAL_HEADER);
         }
 
-        encoder.set_rate(decoder.rate() as i32);
+        let sample_rate = options
+            .and_then(|o| o.sample_rate)
+            .map_or(decoder.rate() as i32, |rate| rate as i32);
+        encoder.set_rate(sample_rate);
         encoder.set_channel_layout(channel_layout);
         encoder.set_channels(channel_layout.channels());
         if let Some(format) = codec
@@ -98,8 +165,8 @@ impl<'a> FFmpegTranscode<'a> {
         encoder.set_bit_rate(bitrate);
         encoder.set_max_bit_rate(max_bitrate);
 
-        encoder.set_time_base((1, decoder.rate() as i32));
-        output.set_time_base((1, decoder.rate() as i32));
+        encoder.set_time_base((1, sample_rate));
+        output.set_time_base((1, sample_rate));
 
         let encoder = encoder.open_as(codec)?;
         output.set_parameters(&encoder);
@@ -107,7 +174,15 @@ impl<'a> FFmpegTranscode<'a> {
         let mut filters = Vec::new();
         if let Some(options) = options {
             if options.loudness_normalize {
-                filters.push("loudnorm");
+                filters.push("loudnorm".to_string());
+            }
+            if let Some(resampler) = &options.resampler {
+                // resample last, because loudnorm upsamples to 192kHz
+                filters.push(resample_filter_spec(
+                    resampler,
+                    sample_rate,
+                    encoder.format(),
+                ));
             }
         }
         let filter_spec = if filters.is_empty() {
@@ -118,6 +193,7 @@ impl<'a> FFmpegTranscode<'a> {
         let filter = Self::build_filter(&filter_spec, &decoder, &encoder)?;
 
         let in_time_base = decoder.time_base();
+        let encoder_time_base = ffmpeg::Rational::new(1, sample_rate);
         let out_time_base = output.time_base();
         let started = Instant::now();
 
@@ -129,6 +205,7 @@ impl<'a> FFmpegTranscode<'a> {
             decoder,
             encoder,
             in_time_base,
+            encoder_time_base,
             out_time_base,
             duration: input.duration().unsigned_abs(),
             total_frames: total_frames as u64,
@@ -169,7 +246,6 @@ impl<'a> FFmpegTranscode<'a> {
 
             out.set_sample_format(encoder.format());
             out.set_channel_layout(encoder.channel_layout());
-            // todo: sample rate here
             out.set_sample_rate(encoder.rate());
         }
 
@@ -206,7 +282,9 @@ impl<'a> FFmpegTranscode<'a> {
         let mut encoded = ffmpeg::Packet::empty();
         while self.encoder.receive_packet(&mut encoded).is_ok() {
             encoded.set_stream(0);
-            encoded.rescale_ts(self.in_time_base, self.out_time_base);
+            // packets are timed in the encoder time base, which differs from
+            // the decoder time base as soon as the sample rate is changed
+            encoded.rescale_ts(self.encoder_time_base, self.out_time_base);
             encoded.write_interleaved(octx)?;
         }
         Ok(())
@@ -357,3 +435,62 @@ impl Transcoder for FFmpegTranscoder {
             .map_err(|err| Error::Custom(err.into()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FFmpegTranscoder;
+    use crate::{Codec, TranscodeProgress, TranscoderOptions};
+    use djtool_ffmpeg as ffmpeg;
+    use std::io::Write;
+    use std::path::Path;
+
+    /// Writes one second of a 440 Hz sine as mono 16 bit wave file
+    fn write_sine_wav(path: &Path, sample_rate: u32) -> std::io::Result<()> {
+        let data_len = sample_rate * 2;
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(b"RIFF")?;
+        file.write_all(&(36 + data_len).to_le_bytes())?;
+        file.write_all(b"WAVEfmt ")?;
+        file.write_all(&16u32.to_le_bytes())?;
+        file.write_all(&1u16.to_le_bytes())?;
+        file.write_all(&1u16.to_le_bytes())?;
+        file.write_all(&sample_rate.to_le_bytes())?;
+        file.write_all(&(sample_rate * 2).to_le_bytes())?;
+        file.write_all(&2u16.to_le_bytes())?;
+        file.write_all(&16u16.to_le_bytes())?;
+        file.write_all(b"data")?;
+        file.write_all(&data_len.to_le_bytes())?;
+        for i in 0..sample_rate {
+            let phase = 2.0 * std::f64::consts::PI * 440.0 * f64::from(i) / f64::from(sample_rate);
+            file.write_all(&((phase.sin() * 16_000.0) as i16).to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_resampled_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("sine.wav");
+        // matroska stores the packet timestamps, unlike wave
+        let output = dir.path().join("sine.mka");
+        write_sine_wav(&input, 48_000).unwrap();
+
+        let options = TranscoderOptions {
+            codec: Some(Codec::PCM),
+            sample_rate: Some(22_050),
+            ..TranscoderOptions::default()
+        };
+        FFmpegTranscoder::new()
+            .transcode(
+                &input,
+                &output,
+                Some(&options),
+                &mut |_: TranscodeProgress| {},
+            )
+            .unwrap();
+
+        let ictx = ffmpeg::format::input(&output).unwrap();
+        let duration = ictx.duration() as f64 * f64::from(ffmpeg::rescale::TIME_BASE);
+        assert!((duration - 1.0).abs() < 0.05, "duration is {duration}s");
+    }
+}
@@ -21,6 +21,71 @@ pub type ProgressHandlerFunc = dyn FnMut(TranscodeProgress);
 pub enum Codec {
     MP3,
     PCM,
+    AIFF,
+}
+
+/// Resampling engine used for sample rate and format conversion
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResampleEngine {
+    /// The builtin ffmpeg software resampler (swr)
+    #[default]
+    Software,
+    /// The SoX resampler (soxr), requires ffmpeg to be built with libsoxr
+    SoundExchange,
+}
+
+/// Dither method applied when reducing the sample bit depth (e.g. 24 to 16 bit)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dither {
+    #[default]
+    None,
+    Rectangular,
+    Triangular,
+    TriangularHighPass,
+    NoiseShapingLipshitz,
+    NoiseShapingFWeighted,
+    NoiseShapingModifiedEWeighted,
+    NoiseShapingImprovedEWeighted,
+    NoiseShapingShibata,
+    NoiseShapingLowShibata,
+    NoiseShapingHighShibata,
+}
+
+/// Interpolation filter of the software resampler
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResampleFilter {
+    Cubic,
+    #[default]
+    BlackmanNuttall,
+    Kaiser,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ResamplerOptions {
+    pub engine: ResampleEngine,
+    /// Precision in bits of the soxr resampler (15 to 33, 28 is very high quality)
+    pub precision: Option<u32>,
+    pub dither: Dither,
+    /// Interpolation filter of the swr resampler
+    pub filter: Option<ResampleFilter>,
+    /// Length of the swr interpolation filter
+    pub filter_size: Option<u32>,
+}
+
+impl ResamplerOptions {
+    /// High quality resampling suitable for 44.1kHz/16-bit output without aliasing
+    ///
+    /// Uses the builtin swr resampler with a longer filter, since soxr is only
+    /// available when ffmpeg is built with libsoxr.
+    #[must_use] pub fn high_quality() -> Self {
+        Self {
+            engine: ResampleEngine::Software,
+            precision: None,
+            dither: Dither::Triangular,
+            filter: Some(ResampleFilter::BlackmanNuttall),
+            filter_size: Some(64),
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -29,6 +94,7 @@ pub struct TranscoderOptions {
     pub bitrate_kbps: Option<usize>,
     pub sample_rate: Option<usize>,
     pub loudness_normalize: bool,
+    pub resampler: Option<ResamplerOptions>,
 }
 
 impl TranscoderOptions {
@@ -38,6 +104,17 @@ impl TranscoderOptions {
             bitrate_kbps: Some(192),
             sample_rate: None,
             loudness_normalize: true,
+            resampler: None,
+        }
+    }
+
+    #[must_use] pub fn aiff() -> Self {
+        Self {
+            codec: Some(Codec::AIFF),
+            bitrate_kbps: None,
+            sample_rate: Some(44_100),
+            loudness_normalize: false,
+            resampler: Some(ResamplerOptions::high_quality()),
         }
     }
 
@@ -48,6 +125,7 @@ impl TranscoderOptions {
             // most importantly, we resample
             sample_rate: Some(22_050),
             loudness_normalize: false,
+            resampler: None,
         }
     }
 }
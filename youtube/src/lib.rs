@@ -1,3 +1,5 @@
 #![allow(warnings)]
+pub mod model;
 pub mod source;
 pub mod sink;
+pub mod stream;
@@ -0,0 +1,156 @@
+use djtool_model as model;
+use serde::{Deserialize, Serialize};
+
+/// Results of a request and the token to continue with, if there are more
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Page<T>
+where
+    T: Serialize,
+{
+    pub continuation: Option<String>,
+    pub results: Vec<T>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct YoutubeVideo {
+    pub title: String,
+    pub video_id: String,
+    pub channel: Option<String>,
+    pub duration_millis: Option<u64>,
+    pub view_count: Option<u64>,
+    pub thumbnails: Vec<Thumbnail>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Thumbnail {
+    pub url: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+}
+
+/// Parses a duration such as `1:02:03` or `3:45` into milliseconds
+pub fn parse_duration_millis(text: &str) -> Option<u64> {
+    text.trim()
+        .split(':')
+        .try_fold(0u64, |acc, part| {
+            let value: u64 = part.trim().parse().ok()?;
+            Some(acc * 60 + value)
+        })
+        .map(|secs| secs * 1000)
+}
+
+/// Parses a view count such as `1,234,567 views` by ignoring all non digits
+pub fn parse_view_count(text: &str) -> Option<u64> {
+    let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+impl From<YoutubeVideo> for model::Track {
+    fn from(video: YoutubeVideo) -> model::Track {
+        let mut thumbnails = video
+            .thumbnails
+            .into_iter()
+            .filter_map(|thumbnail| {
+                Some(model::Artwork {
+                    url: thumbnail.url?,
+                    width: thumbnail.width.unwrap_or(0).max(0) as u32,
+                    height: thumbnail.height.unwrap_or(0).max(0) as u32,
+                })
+            })
+            .collect::<Vec<model::Artwork>>();
+        thumbnails.sort_by(|b, a| (a.width * a.height).cmp(&(b.width * b.height)));
+        let artwork = thumbnails.into_iter().next();
+
+        // the uploading channel is the best guess for the artist
+        let channel = video.channel.unwrap_or_default();
+        let artists = if channel.is_empty() {
+            Vec::new()
+        } else {
+            vec![model::Artist {
+                id: None, // channel IDs are not parsed
+                name: channel.to_owned(),
+            }]
+        };
+
+        model::Track {
+            id: Some(model::TrackId {
+                source: model::Service::Youtube as i32,
+                id: video.video_id,
+                playlist_id: None, // unknown at this point
+            }),
+            name: video.title,
+            duration_millis: video.duration_millis.unwrap_or(0),
+            artwork,
+            preview: None,
+            artist: channel.to_owned(),
+            artists,
+            info: Some(model::track::Info::YoutubeTrack(model::YoutubeTrack {
+                channel,
+                view_count: video.view_count.unwrap_or(0),
+            })),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_millis() {
+        assert_eq!(parse_duration_millis("3:45"), Some(225_000));
+        assert_eq!(parse_duration_millis(" 1:02:03 "), Some(3_723_000));
+        assert_eq!(parse_duration_millis("42"), Some(42_000));
+        assert_eq!(parse_duration_millis(""), None);
+        assert_eq!(parse_duration_millis("LIVE"), None);
+        assert_eq!(parse_duration_millis("3:"), None);
+    }
+
+    #[test]
+    fn test_parse_view_count() {
+        assert_eq!(parse_view_count("1,234,567 views"), Some(1_234_567));
+        assert_eq!(parse_view_count("1 view"), Some(1));
+        assert_eq!(parse_view_count("No views"), None);
+        assert_eq!(parse_view_count(""), None);
+    }
+
+    #[test]
+    fn test_track_from_video() {
+        let thumbnail = |url: &str, width: i32, height: i32| Thumbnail {
+            url: Some(url.to_string()),
+            width: Some(width),
+            height: Some(height),
+        };
+        let video = YoutubeVideo {
+            title: "Song".to_string(),
+            video_id: "dQw4w9WgXcQ".to_string(),
+            channel: Some("Artist".to_string()),
+            duration_millis: parse_duration_millis("3:33"),
+            view_count: parse_view_count("1,234 views"),
+            thumbnails: vec![
+                thumbnail("small", 120, 90),
+                thumbnail("large", 480, 360),
+                Thumbnail {
+                    url: None,
+                    width: Some(1280),
+                    height: Some(720),
+                },
+            ],
+        };
+        let track = model::Track::from(video);
+        assert_eq!(track.id.unwrap().id, "dQw4w9WgXcQ");
+        assert_eq!(track.duration_millis, 213_000);
+        assert_eq!(track.artwork.unwrap().url, "large");
+        assert_eq!(track.artist, "Artist");
+        assert_eq!(track.artists[0].name, "Artist");
+        assert_eq!(
+            track.info,
+            Some(model::track::Info::YoutubeTrack(model::YoutubeTrack {
+                channel: "Artist".to_string(),
+                view_count: 1234,
+            }))
+        );
+    }
+}
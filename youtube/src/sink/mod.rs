@@ -1,3 +1,20 @@
+use djtool_model as model;
+
+/// What the youtube sink supports
+///
+/// Public videos are searched and downloaded without a login.
+pub fn capabilities() -> model::Capabilities {
+    model::Capabilities {
+        search: true,
+        playlists: true,
+        user_library: false,
+        previews: false,
+        downloads: true,
+        requires_login: false,
+        // the limits of the innertube api are not documented
+        rate_limit: None,
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -1,6 +1,9 @@
+use google_youtube3 as youtube3;
 use youtube3::{chrono, hyper, hyper_rustls, oauth2, Error, FieldMask, Result, YouTube};
 
-pub async fn test() -> () {
+pub type Hub = YouTube<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>;
+
+pub async fn test(hub: &Hub) -> () {
     let result = hub
         .playlists()
         .list(&vec!["nonumy".into()])
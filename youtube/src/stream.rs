@@ -0,0 +1,134 @@
+use crate::model::Page;
+use async_stream::stream;
+use djtool_model::source::QueryProgress;
+use futures::stream::Stream;
+use futures::Future;
+use serde::Serialize;
+use std::time::Instant;
+
+pub fn paginate<'a, T: 'a, E: 'a, Fut, Req: 'a>(
+    req: Req,
+) -> impl Stream<Item = Result<T, E>> + 'a + Send
+where
+    T: Unpin + Serialize + Send,
+    E: Send,
+    Fut: Future<Output = Result<Page<T>, E>> + Send,
+    Req: Fn(Option<String>) -> Fut + Send + Sync,
+{
+    paginate_with_progress(req, String::new(), Box::new(|_| {}))
+}
+
+/// Like `paginate`, but reports the progress of `query` after every continuation
+///
+/// Youtube does not report the total number of results.
+pub fn paginate_with_progress<'a, T: 'a, E: 'a, Fut, Req: 'a>(
+    req: Req,
+    query: String,
+    progress: Box<dyn Fn(QueryProgress) + Send + 'static>,
+) -> impl Stream<Item = Result<T, E>> + 'a + Send
+where
+    T: Unpin + Serialize + Send,
+    E: Send,
+    Fut: Future<Output = Result<Page<T>, E>> + Send,
+    Req: Fn(Option<String>) -> Fut + Send + Sync,
+{
+    let mut continuation: Option<String> = None;
+    let started = Instant::now();
+    let mut state = QueryProgress {
+        query,
+        ..QueryProgress::default()
+    };
+    Box::pin(stream! {
+        loop {
+            let page = req(continuation).await?;
+            continuation = page.continuation;
+            state.pages += 1;
+            state.items += page.results.len();
+            state.elapsed = started.elapsed();
+            progress(state.clone());
+            for result in page.results.into_iter() {
+                yield Ok(result);
+            }
+            if continuation.is_none() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{paginate, paginate_with_progress};
+    use crate::model::Page;
+    use djtool_model::source::QueryProgress;
+    use futures::{StreamExt, TryStreamExt};
+    use std::sync::{Arc, Mutex};
+
+    /// Search results continued by opaque tokens, including an empty page
+    fn search_page(continuation: Option<&str>) -> Result<Page<&'static str>, String> {
+        match continuation {
+            None => Ok(Page {
+                continuation: Some("EpYDEgVxdWVyeRqKA".to_string()),
+                results: vec!["dQw4w9WgXcQ", "yPYZpwSpKmA"],
+            }),
+            Some("EpYDEgVxdWVyeRqKA") => Ok(Page {
+                continuation: Some("EoIDEgVxdWVyeRr-Ag".to_string()),
+                results: vec![],
+            }),
+            Some("EoIDEgVxdWVyeRr-Ag") => Ok(Page {
+                continuation: None,
+                results: vec!["kJQP7kiw5Fk"],
+            }),
+            Some(token) => Err(format!("unknown continuation {token}")),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paginate_follows_continuations() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let progress = {
+            let reports = reports.clone();
+            Box::new(move |state: QueryProgress| reports.lock().unwrap().push(state))
+        };
+        let videos: Vec<&str> = paginate_with_progress(
+            |continuation: Option<String>| {
+                requested.lock().unwrap().push(continuation.clone());
+                async move { search_page(continuation.as_deref()) }
+            },
+            "query".to_string(),
+            progress,
+        )
+        .try_collect()
+        .await
+        .unwrap();
+        assert_eq!(videos, vec!["dQw4w9WgXcQ", "yPYZpwSpKmA", "kJQP7kiw5Fk"]);
+
+        // tokens are passed back verbatim and an empty page does not end the search
+        assert_eq!(
+            *requested.lock().unwrap(),
+            vec![
+                None,
+                Some("EpYDEgVxdWVyeRqKA".to_string()),
+                Some("EoIDEgVxdWVyeRr-Ag".to_string()),
+            ]
+        );
+
+        // youtube never reports the total number of results
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.last().map(|state| state.items), Some(3));
+        assert!(reports.iter().all(|state| state.total.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_paginate_stops_at_error() {
+        let results: Vec<Result<&str, String>> =
+            paginate(|_: Option<String>| async { search_page(Some("expired")) })
+                .collect()
+                .await;
+        assert_eq!(
+            results,
+            vec![Err("unknown continuation expired".to_string())]
+        );
+    }
+}